@@ -0,0 +1,42 @@
+//! Times the day 1 calibration sum on a large generated document, comparing the
+//! automaton based `map_calibration_sum` with the byte scanner:
+//! `cargo run --release --example day1_bench -- [megabytes, default 300]`
+use aoc2023::{
+    day1::{generate_document, map_calibration_sum, ByteScanner, DigitVocabulary, ParseMode},
+    rng::XorShift,
+};
+use std::{env, time::Instant};
+
+fn main() {
+    let megabytes: usize = env::args()
+        .nth(1)
+        .map(|mb| mb.parse().expect("size in megabytes"))
+        .unwrap_or(300);
+
+    let now = Instant::now();
+    let doc = generate_document(&mut XorShift::new(1), megabytes << 20);
+    println!(
+        "generated {} lines ({megabytes} MiB) in {:?}",
+        doc.lines().count(),
+        now.elapsed()
+    );
+
+    let english = DigitVocabulary::english();
+    for mode in [ParseMode::Digits, ParseMode::DigitsAndWords] {
+        let now = Instant::now();
+        let expected = map_calibration_sum(&doc, &english, mode).unwrap();
+        let slow = now.elapsed();
+
+        let scanner = ByteScanner::new(&english, mode);
+        let now = Instant::now();
+        let sum = scanner.calibration_sum(doc.as_bytes()).unwrap();
+        let fast = now.elapsed();
+
+        assert_eq!(sum, u64::from(expected), "{mode:?}");
+        println!(
+            "{mode:?}: {sum}, map_calibration_sum {slow:?} ({:.0} MiB/s), byte scanner {fast:?} ({:.0} MiB/s)",
+            megabytes as f64 / slow.as_secs_f64(),
+            megabytes as f64 / fast.as_secs_f64()
+        );
+    }
+}
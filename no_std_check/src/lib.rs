@@ -0,0 +1,75 @@
+//! Builds the solvers without `std`, so anything in them reaching for it fails to compile here
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use aoc2023::day1::{DigitVocabulary, ParseMode};
+use aoc2023::{day1, day10, day11, day2, day3, day4, day5, day6, day7, day8, day9};
+
+/// Every day's answer for `part`, or `None` for a day or part that doesn't exist
+/// and for input day 1 can't read
+pub fn solve(day: u8, part: u8, input: &str) -> Option<String> {
+    let english = DigitVocabulary::english();
+    let answer = match (day, part) {
+        (1, 1) => day1::map_calibration_sum(input, &english, ParseMode::Digits)
+            .ok()?
+            .to_string(),
+        (1, 2) => day1::map_calibration_sum(input, &english, ParseMode::DigitsAndWords)
+            .ok()?
+            .to_string(),
+        (2, 1) => day2::run_part_1(input, day2::GameLimits::<u32>::new(12, 13, 14)).to_string(),
+        (2, 2) => day2::run_part_2::<u32>(input).to_string(),
+        (3, 1) => day3::run_part_1::<u32>(input).to_string(),
+        (3, 2) => day3::run_part_2::<u32>(input).to_string(),
+        (4, 1) => day4::run_part_1::<u32>(input).to_string(),
+        (4, 2) => day4::run_part_2::<u32>(input).to_string(),
+        (5, 1) => day5::run_part_1(input).to_string(),
+        (5, 2) => day5::run_part_2(input).to_string(),
+        (6, 1) => day6::run_part_1::<u64>(input).to_string(),
+        (6, 2) => day6::run_part_2::<u64>(input).to_string(),
+        (7, 1) => day7::run_part_1::<u32>(input).to_string(),
+        (7, 2) => day7::run_part_2::<u32>(input).to_string(),
+        (8, 1) => day8::run_part_1(input).to_string(),
+        (8, 2) => day8::run_part_2(input).to_string(),
+        (9, 1) => day9::run_part_1::<i64>(input).to_string(),
+        (9, 2) => day9::run_part_2::<i64>(input).to_string(),
+        (10, 1) => day10::run_part_1(input).to_string(),
+        (10, 2) => day10::run_part_2(input).to_string(),
+        (11, 1) => day11::run_part_1::<u64>(input).to_string(),
+        (11, 2) => day11::run_part_2::<u64>(input).to_string(),
+        _ => return None,
+    };
+    Some(answer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn known_inputs() {
+        let day2 = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+        assert_eq!(solve(2, 1, day2).as_deref(), Some("8"));
+        assert_eq!(solve(2, 2, day2).as_deref(), Some("2286"));
+
+        let day6 = "Time:      7  15   30
+Distance:  9  40  200
+";
+        assert_eq!(solve(6, 1, day6).as_deref(), Some("288"));
+        assert_eq!(solve(6, 2, day6).as_deref(), Some("71503"));
+
+        let day9 = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+        assert_eq!(solve(9, 1, day9).as_deref(), Some("114"));
+        assert_eq!(solve(9, 2, day9).as_deref(), Some("2"));
+        assert_eq!(solve(12, 1, day9), None);
+    }
+}
@@ -0,0 +1,258 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::cmp::Reverse;
+
+// Marks a missing trie edge while building, every edge is filled in by the end
+const NONE: u32 = u32::MAX;
+
+/// One occurrence of a pattern, `haystack[start..end]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Index into the patterns the automaton was built from
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Match {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Finds every occurrence of a set of byte patterns in a single pass over the haystack.
+/// Patterns may overlap or contain each other, empty patterns never match.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    // dense transitions, 256 per state, state 0 is the root
+    delta: Vec<u32>,
+    // the patterns ending at each state, including those ending at its suffixes
+    outputs: Vec<Vec<usize>>,
+    lens: Vec<usize>,
+}
+
+impl AhoCorasick {
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        Self::build(patterns, false)
+    }
+
+    /// Matches ASCII letters regardless of case, other bytes must match exactly
+    pub fn ascii_case_insensitive<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        Self::build(patterns, true)
+    }
+
+    fn build<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>, fold_case: bool) -> Self {
+        let mut delta = vec![NONE; 256];
+        let mut outputs = vec![Vec::new()];
+        let mut lens = Vec::new();
+
+        for (i, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref();
+            lens.push(pattern.len());
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut state = 0;
+            for &b in pattern {
+                let b = if fold_case { b.to_ascii_lowercase() } else { b };
+                let edge = state * 256 + b as usize;
+                if delta[edge] == NONE {
+                    delta[edge] = outputs.len() as u32;
+                    delta.extend_from_slice(&[NONE; 256]);
+                    outputs.push(Vec::new());
+                }
+                state = delta[edge] as usize;
+            }
+            outputs[state].push(i);
+        }
+
+        // Breadth first, so a state's suffix link is always finished before the state itself
+        let mut fail = vec![0; outputs.len()];
+        let mut queue = VecDeque::new();
+        for edge in delta.iter_mut().take(256) {
+            if *edge == NONE {
+                *edge = 0;
+            } else {
+                queue.push_back(*edge as usize);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            for b in 0..256 {
+                let next = delta[state * 256 + b];
+                let via_fail = delta[fail[state] * 256 + b];
+                if next == NONE {
+                    delta[state * 256 + b] = via_fail;
+                } else {
+                    let next = next as usize;
+                    fail[next] = via_fail as usize;
+                    let inherited = outputs[via_fail as usize].clone();
+                    outputs[next].extend(inherited);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        // upper case letters follow the same edges as lower case ones
+        if fold_case {
+            for state in delta.chunks_exact_mut(256) {
+                for upper in b'A'..=b'Z' {
+                    state[upper as usize] = state[upper.to_ascii_lowercase() as usize];
+                }
+            }
+        }
+
+        AhoCorasick {
+            delta,
+            outputs,
+            lens,
+        }
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.lens.len()
+    }
+
+    /// Every match, overlapping ones included, in order of where they end
+    pub fn find_overlapping<'a, 'h>(&'a self, haystack: &'h [u8]) -> Overlapping<'a, 'h> {
+        Overlapping {
+            automaton: self,
+            haystack,
+            pos: 0,
+            state: 0,
+            output: 0,
+        }
+    }
+
+    /// The match starting furthest left and the one starting furthest right,
+    /// the longest wins when several start at the same place
+    pub fn first_and_last(&self, haystack: &[u8]) -> Option<(Match, Match)> {
+        self.first_and_last_where(haystack, |_| true)
+    }
+
+    /// As `first_and_last`, only counting the matches `accept` keeps
+    pub fn first_and_last_where(
+        &self,
+        haystack: &[u8],
+        mut accept: impl FnMut(&Match) -> bool,
+    ) -> Option<(Match, Match)> {
+        self.find_overlapping(haystack)
+            .filter(|m| accept(m))
+            .fold(None, |found, m| match found {
+                None => Some((m, m)),
+                Some((first, last)) => Some((
+                    if (m.start, Reverse(m.len())) < (first.start, Reverse(first.len())) {
+                        m
+                    } else {
+                        first
+                    },
+                    if (m.start, m.len()) > (last.start, last.len()) {
+                        m
+                    } else {
+                        last
+                    },
+                )),
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Overlapping<'a, 'h> {
+    automaton: &'a AhoCorasick,
+    haystack: &'h [u8],
+    pos: usize,
+    state: usize,
+    // how many of the current state's outputs have been yielded
+    output: usize,
+}
+
+impl Iterator for Overlapping<'_, '_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&pattern) = self.automaton.outputs[self.state].get(self.output) {
+                self.output += 1;
+                return Some(Match {
+                    pattern,
+                    start: self.pos - self.automaton.lens[pattern],
+                    end: self.pos,
+                });
+            }
+
+            let &b = self.haystack.get(self.pos)?;
+            self.state = self.automaton.delta[self.state * 256 + b as usize] as usize;
+            self.pos += 1;
+            self.output = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Every match found by checking each pattern at each position
+    fn naive(patterns: &[&str], haystack: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        for start in 0..haystack.len() {
+            for (pattern, p) in patterns.iter().enumerate() {
+                if !p.is_empty() && haystack[start..].starts_with(p) {
+                    matches.push(Match {
+                        pattern,
+                        start,
+                        end: start + p.len(),
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    fn sorted(mut matches: Vec<Match>) -> Vec<Match> {
+        matches.sort_by_key(|m| (m.start, m.end, m.pattern));
+        matches
+    }
+
+    #[test]
+    fn overlapping_and_nested() {
+        let patterns = ["he", "she", "his", "hers", "e", "", "ushers"];
+        let haystack = "ushers and his sheep";
+        let automaton = AhoCorasick::new(patterns);
+
+        let found: Vec<_> = automaton.find_overlapping(haystack.as_bytes()).collect();
+        assert_eq!(sorted(found), sorted(naive(&patterns, haystack)));
+        assert_eq!(automaton.pattern_count(), 7);
+    }
+
+    #[test]
+    fn first_and_last_prefer_longest() {
+        // "bc" ends first but "abcd" starts first
+        let automaton = AhoCorasick::new(["bc", "abcd", "d", "cd"]);
+        let (first, last) = automaton.first_and_last(b"xabcd").unwrap();
+        assert_eq!((first.pattern, first.start), (1, 1));
+        assert_eq!((last.pattern, last.start), (2, 4));
+
+        let automaton = AhoCorasick::new(["seven", "seventeen"]);
+        let (first, last) = automaton.first_and_last(b"seventeen").unwrap();
+        assert_eq!(first.pattern, 1);
+        assert_eq!(last.pattern, 1);
+
+        assert_eq!(automaton.first_and_last(b"six"), None);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let automaton = AhoCorasick::ascii_case_insensitive(["Two", "one"]);
+        let found: Vec<_> = automaton
+            .find_overlapping(b"TWONE twone")
+            .map(|m| (m.pattern, m.start))
+            .collect();
+        assert_eq!(found, [(0, 0), (1, 2), (0, 6), (1, 8)]);
+
+        assert_eq!(AhoCorasick::new(["one"]).first_and_last(b"ONE"), None);
+    }
+}
@@ -0,0 +1,159 @@
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::alloc::System;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether `CountingAllocator` is installed as the global allocator,
+/// without it `measure` only sees allocations made through it directly
+pub const ENABLED: bool = cfg!(feature = "alloc-profile");
+
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Forwards to the system allocator, keeping count of what passes through
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        LIVE_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        Self::record_dealloc(layout.size());
+    }
+
+    // a realloc counts as a fresh allocation, it's usually a `Vec` or `String` growing
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllocStats {
+    /// Including reallocations
+    pub allocations: usize,
+    pub allocated_bytes: usize,
+    /// The most heap in use at once, above what was already live beforehand
+    pub peak_bytes: usize,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} total, {} peak",
+            self.allocations,
+            Bytes(self.allocated_bytes),
+            Bytes(self.peak_bytes)
+        )
+    }
+}
+
+struct Bytes(usize);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            b if b < 1 << 10 => write!(f, "{b} B"),
+            b if b < 1 << 20 => write!(f, "{:.1} KiB", b as f64 / (1 << 10) as f64),
+            b => write!(f, "{:.1} MiB", b as f64 / (1 << 20) as f64),
+        }
+    }
+}
+
+/// Counts the allocations made while running `f`.
+/// The counters are global, so anything allocating on another thread meanwhile is counted too.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let live = LIVE_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(live, Ordering::Relaxed);
+
+    let out = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes,
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(live),
+    };
+    (out, stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_direct_allocations() {
+        let (_, stats) = measure(|| unsafe {
+            let allocator = CountingAllocator;
+            let small = Layout::from_size_align(64, 8).unwrap();
+            let big = Layout::from_size_align(4096, 8).unwrap();
+
+            let a = allocator.alloc(small);
+            let b = allocator.alloc_zeroed(big);
+            allocator.dealloc(b, big);
+            let a = allocator.realloc(a, small, 128);
+            allocator.dealloc(a, Layout::from_size_align(128, 8).unwrap());
+        });
+
+        // other tests may be allocating at the same time if the allocator is installed
+        assert!(stats.allocations >= 3);
+        assert!(stats.allocated_bytes >= 64 + 4096 + 128);
+        assert!(stats.peak_bytes >= 64 + 4096);
+    }
+
+    #[test]
+    fn human_readable() {
+        let stats = AllocStats {
+            allocations: 3,
+            allocated_bytes: 3 << 20,
+            peak_bytes: 1536,
+        };
+        assert_eq!(
+            stats.to_string(),
+            "3 allocations, 3.0 MiB total, 1.5 KiB peak"
+        );
+        assert_eq!(Bytes(12).to_string(), "12 B");
+    }
+}
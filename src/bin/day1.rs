@@ -0,0 +1,49 @@
+use aoc2023::{
+    day1::{calibration_report, map_calibration_sum, DigitVocabulary, ParseMode, Strictness},
+    runner::Runner,
+};
+use std::env;
+
+fn main() {
+    let input = include_str!("../../assets/day1Input.txt");
+    let mut runner = Runner::new(
+        1,
+        input,
+        &[include_str!("../day1.rs"), include_str!("day1.rs")],
+    );
+
+    // `--words <name or file>` picks the digit words, english by default
+    let args: Vec<String> = env::args().collect();
+    let vocabulary = match args.iter().position(|a| a == "--words") {
+        Some(i) => {
+            let words = args
+                .get(i + 1)
+                .expect("--words needs a vocabulary name or file");
+            DigitVocabulary::by_name(words)
+                .map(Ok)
+                .unwrap_or_else(|| DigitVocabulary::load(words))
+                .unwrap()
+        }
+        None => DigitVocabulary::english(),
+    };
+    // the answers depend on the words and on which mode each part uses
+    let modes = [ParseMode::Digits, ParseMode::DigitsAndWords];
+    runner = runner.with_parameters((&vocabulary, modes));
+    runner.part(1, |input| {
+        map_calibration_sum(input, &vocabulary, modes[0]).unwrap()
+    });
+    runner.part(2, |input| {
+        map_calibration_sum(input, &vocabulary, modes[1]).unwrap()
+    });
+
+    // `--report` shows where each line's digits were found, skipping any without
+    if args.iter().any(|a| a == "--report") {
+        let report = calibration_report(
+            input,
+            &vocabulary,
+            ParseMode::DigitsAndWords,
+            Strictness::Lenient,
+        );
+        println!("{}", report.unwrap());
+    }
+}
@@ -0,0 +1,28 @@
+use aoc2023::{
+    day10::{record_part_2, run_part_1, run_part_2},
+    runner::Runner,
+    visualize,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day10Input.txt");
+    let mut runner = Runner::new(
+        10,
+        input,
+        &[include_str!("../day10.rs"), include_str!("day10.rs")],
+    );
+    runner.part(1, run_part_1);
+
+    // `--frames <dir>` records the flood fill, the real input takes ~20k steps
+    match visualize::recorder_from_args() {
+        Some(recorder) => {
+            let mut recorder = recorder.every(100);
+            // a cached answer would never get to record anything
+            runner = runner.without_cache();
+            runner.part(2, |input| record_part_2(input, &mut recorder).unwrap());
+        }
+        None => {
+            runner.part(2, run_part_2);
+        }
+    }
+}
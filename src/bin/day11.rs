@@ -0,0 +1,21 @@
+use aoc2023::{
+    day11::{record_expansion, run_part_1, run_part_2},
+    runner::Runner,
+    visualize,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day11Input.txt");
+    if let Some(mut recorder) = visualize::recorder_from_args() {
+        record_expansion(input, &mut recorder).unwrap();
+    }
+    let mut runner = Runner::new(
+        11,
+        input,
+        &[include_str!("../day11.rs"), include_str!("day11.rs")],
+    );
+    runner.part(1, run_part_1::<usize>);
+    runner.part(2, run_part_2::<usize>);
+    // 293152727264 -> incorrect (forgot to change column expansion too)
+    // 611998089572 -> correct
+}
@@ -0,0 +1,49 @@
+use aoc2023::{
+    day2::{query, run_part_1_with, run_part_2_with, statistics, validate, GameLimits, Validation},
+    runner::Runner,
+};
+use std::env;
+
+fn main() {
+    let input = include_str!("../../assets/day2Input.txt");
+    let mut runner = Runner::new(
+        2,
+        input,
+        &[include_str!("../day2.rs"), include_str!("day2.rs")],
+    );
+
+    // `--warn` lists anything suspicious in the games, `--strict` refuses to solve if there is any
+    let validation = match env::args().find(|a| a == "--warn" || a == "--strict") {
+        Some(flag) if flag == "--strict" => Validation::Strict,
+        Some(_) => Validation::Warn,
+        None => Validation::Lenient,
+    };
+    let issues =
+        validate::<u32>(input, validation).unwrap_or_else(|e| panic!("invalid games:\n{e}"));
+    for issue in issues {
+        eprintln!("warning: {issue}");
+    }
+
+    let limit = GameLimits::<u32>::new(12, 13, 14);
+    runner = runner.with_parameters((&limit, validation));
+    runner.part(1, |input| run_part_1_with(input, limit, validation));
+    runner.part(2, |input| run_part_2_with::<u32>(input, validation));
+
+    // `--query "<query>"`, e.g. `--query "max power where hands >= 3"`, see `day2::Query`
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--query") {
+        let q = args.get(i + 1).expect("--query needs a query");
+        runner.run(q, |input| {
+            query::<u32>(input, q).unwrap_or_else(|e| panic!("{e}"))
+        });
+    }
+
+    // `--stats` prints a table of per color statistics, `--stats json` the same as JSON
+    if let Some(i) = args.iter().position(|a| a == "--stats") {
+        let stats = statistics::<u32>(input, 0.5);
+        match args.get(i + 1).map(String::as_str) {
+            Some("json") => println!("{}", stats.to_json()),
+            _ => println!("{stats}"),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use aoc2023::{
+    day3::{render_schematic, run_part_1, run_part_2, stream_sums, PartGraph, SchematicRules},
+    runner::Runner,
+    visualize,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day3Input.txt");
+    if let Some(mut recorder) = visualize::recorder_from_args() {
+        recorder.write(&render_schematic(input)).unwrap();
+    }
+    // `--dot` prints only how numbers and symbols connect, so it can be piped to Graphviz
+    if std::env::args().any(|a| a == "--dot") {
+        print!(
+            "{}",
+            PartGraph::<u32>::new(input, &SchematicRules::default()).to_dot()
+        );
+        return;
+    }
+    let mut runner = Runner::new(
+        3,
+        input,
+        &[include_str!("../day3.rs"), include_str!("day3.rs")],
+    );
+    runner.part(1, run_part_1::<u32>);
+    runner.part(2, run_part_2::<u32>);
+
+    // `--stream` checks both answers against the three line window scanner
+    if std::env::args().any(|a| a == "--stream") {
+        runner.run("streamed", |input| {
+            let (parts, ratios) = stream_sums::<u32, _>(input.lines(), &SchematicRules::default());
+            format!("{parts}, {ratios}")
+        });
+    }
+}
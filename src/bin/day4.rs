@@ -0,0 +1,15 @@
+use aoc2023::{
+    day4::{run_part_1, run_part_2},
+    runner::Runner,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day4Input.txt");
+    let mut runner = Runner::new(
+        4,
+        input,
+        &[include_str!("../day4.rs"), include_str!("day4.rs")],
+    );
+    runner.part(1, run_part_1::<u32>);
+    runner.part(2, run_part_2::<u32>);
+}
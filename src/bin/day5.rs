@@ -0,0 +1,15 @@
+use aoc2023::{
+    day5::{run_part_1, run_part_2},
+    runner::Runner,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day5Input.txt");
+    let mut runner = Runner::new(
+        5,
+        input,
+        &[include_str!("../day5.rs"), include_str!("day5.rs")],
+    );
+    runner.part(1, run_part_1);
+    runner.part(2, run_part_2);
+}
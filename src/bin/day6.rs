@@ -0,0 +1,15 @@
+use aoc2023::{
+    day6::{run_part_1, run_part_2},
+    runner::Runner,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day6Input.txt");
+    let mut runner = Runner::new(
+        6,
+        input,
+        &[include_str!("../day6.rs"), include_str!("day6.rs")],
+    );
+    runner.part(1, run_part_1::<usize>);
+    runner.part(2, run_part_2::<usize>);
+}
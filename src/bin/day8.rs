@@ -0,0 +1,15 @@
+use aoc2023::{
+    day8::{run_part_1, run_part_2},
+    runner::Runner,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day8Input.txt");
+    let mut runner = Runner::new(
+        8,
+        input,
+        &[include_str!("../day8.rs"), include_str!("day8.rs")],
+    );
+    runner.part(1, run_part_1);
+    runner.part(2, run_part_2);
+}
@@ -0,0 +1,15 @@
+use aoc2023::{
+    day9::{run_part_1, run_part_2},
+    runner::Runner,
+};
+
+fn main() {
+    let input = include_str!("../../assets/day9Input.txt");
+    let mut runner = Runner::new(
+        9,
+        input,
+        &[include_str!("../day9.rs"), include_str!("day9.rs")],
+    );
+    runner.part(1, run_part_1::<isize>);
+    runner.part(2, run_part_2::<isize>);
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Where answers are cached unless told otherwise, `target/` is already ignored by git
+pub const DEFAULT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/aoc-cache");
+
+/// One file per day, so days run at the same time don't overwrite each other's answers
+pub fn default_path(day: u8) -> PathBuf {
+    Path::new(DEFAULT_DIR).join(format!("day{day}.tsv"))
+}
+
+/// 64-bit FNV-1a, stable across runs and toolchains unlike `DefaultHasher`
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKey {
+    pub day: u8,
+    pub part: u8,
+    /// `hash` of the puzzle input
+    pub input: u64,
+    /// `hash` of whatever identifies the solver's version
+    pub solver: u64,
+}
+
+impl CacheKey {
+    fn to_line(self, answer: &str) -> String {
+        format!(
+            "{}\t{}\t{:016x}\t{:016x}\t{answer}",
+            self.day, self.part, self.input, self.solver
+        )
+    }
+
+    fn from_line(line: &str) -> Option<(Self, String)> {
+        let mut split = line.splitn(5, '\t');
+        let day = split.next()?.parse().ok()?;
+        let part = split.next()?.parse().ok()?;
+        let input = u64::from_str_radix(split.next()?, 16).ok()?;
+        let solver = u64::from_str_radix(split.next()?, 16).ok()?;
+        let answer = split.next()?.to_string();
+        Some((
+            CacheKey {
+                day,
+                part,
+                input,
+                solver,
+            },
+            answer,
+        ))
+    }
+}
+
+/// Solved answers stored on disk as one tab separated line per key
+#[derive(Debug, Clone)]
+pub struct Cache {
+    path: PathBuf,
+    entries: Vec<(CacheKey, String)>,
+}
+
+impl Cache {
+    /// A missing file is an empty cache, malformed lines are dropped
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match fs::read_to_string(&path) {
+            Ok(s) => s.lines().filter_map(CacheKey::from_line).collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Cache { path, entries })
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, answer)| answer.as_str())
+    }
+
+    /// Also evicts anything solved for the same day and part by a different solver version,
+    /// since those answers can never be looked up again
+    pub fn insert(&mut self, key: CacheKey, answer: String) {
+        self.entries.retain(|(k, _)| {
+            !(k.day == key.day && k.part == key.part && (k.solver != key.solver || *k == key))
+        });
+        self.entries.push((key, answer));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut s = String::new();
+        for (key, answer) in &self.entries {
+            s += &key.to_line(answer);
+            s += "\n";
+        }
+        // written aside then renamed over, so a reader never sees half a file
+        let tmp = self
+            .path
+            .with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, s)?;
+        fs::rename(tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(part: u8, input: &str, solver: &str) -> CacheKey {
+        CacheKey {
+            day: 1,
+            part,
+            input: hash(input.as_bytes()),
+            solver: hash(solver.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn fnv_known_values() {
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir().join(format!("aoc2023_cache_{}.tsv", std::process::id()));
+        let mut cache = Cache::open(&path).unwrap();
+        assert!(cache.is_empty());
+
+        cache.insert(key(1, "input", "v1"), "142".to_string());
+        cache.insert(key(2, "input", "v1"), "281".to_string());
+        cache.save().unwrap();
+
+        let cache = Cache::open(&path).unwrap();
+        assert_eq!(cache.get(&key(1, "input", "v1")), Some("142"));
+        assert_eq!(cache.get(&key(2, "input", "v1")), Some("281"));
+        assert_eq!(cache.get(&key(1, "other input", "v1")), None);
+        assert_eq!(cache.get(&key(1, "input", "v2")), None);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn new_solver_evicts_old_answers() {
+        let mut cache = Cache::open(std::env::temp_dir().join("aoc2023_never_saved.tsv")).unwrap();
+        cache.insert(key(1, "example", "v1"), "142".to_string());
+        cache.insert(key(1, "input", "v1"), "54927".to_string());
+        cache.insert(key(2, "input", "v1"), "54581".to_string());
+        assert_eq!(cache.len(), 3);
+
+        cache.insert(key(1, "input", "v2"), "54927".to_string());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&key(1, "example", "v1")), None);
+        assert_eq!(cache.get(&key(2, "input", "v1")), Some("54581"));
+    }
+}
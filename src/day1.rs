@@ -1,81 +1,933 @@
-fn main() {
-    let input = include_str!("../assets/day1Input.txt");
-    let output = map_calibration_sum(input);
-    println!("{output}");
+use crate::{
+    aho_corasick::{AhoCorasick, Match},
+    num::Num,
+    rng::XorShift,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cmp::Reverse, fmt::Display, str::FromStr};
+
+// The zero starting each run of ten decimal digits (general category Nd), as of Unicode 14
+const DECIMAL_ZEROS: [u32; 66] = [
+    0x30, 0x660, 0x6F0, 0x7C0, 0x966, 0x9E6, 0xA66, 0xAE6, 0xB66, 0xBE6, 0xC66, 0xCE6, 0xD66,
+    0xDE6, 0xE50, 0xED0, 0xF20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90,
+    0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10,
+    0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0, 0x11650,
+    0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x16A60, 0x16AC0, 0x16B50,
+    0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E950, 0x1FBF0,
+];
+
+/// The value of a decimal digit from any script, so '3', '٣' (Arabic-Indic), '३' (Devanagari)
+/// and '３' (fullwidth) are all 3. Other numeric characters ('½', 'Ⅻ', '²') aren't digits.
+pub fn decimal_value(c: char) -> Option<u32> {
+    let c = c as u32;
+    let run = DECIMAL_ZEROS
+        .partition_point(|zero| *zero <= c)
+        .checked_sub(1)?;
+    let value = c - DECIMAL_ZEROS[run];
+    (value < 10).then_some(value)
+}
+
+// Whether `word` is a single decimal digit, in any script
+fn is_numeral(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().and_then(decimal_value).is_some() && chars.next().is_none()
+}
+
+// Every script's numeral for `digit`
+fn numerals(digit: u32) -> impl Iterator<Item = (String, u32)> {
+    DECIMAL_ZEROS.iter().map(move |zero| {
+        let numeral = char::from_u32(zero + digit).expect("digits are valid chars");
+        (numeral.to_string(), digit)
+    })
 }
 
-fn _parse_line_only_digits(line: &str) -> Option<u32> {
-    let digits: Vec<&str> = line.matches(char::is_numeric).collect();
+// Words for 0 to 9, `None` where a language has no word for zero
+const ENGLISH: [Option<&str>; 10] = [
+    Some("zero"),
+    Some("one"),
+    Some("two"),
+    Some("three"),
+    Some("four"),
+    Some("five"),
+    Some("six"),
+    Some("seven"),
+    Some("eight"),
+    Some("nine"),
+];
+const ENGLISH_COMPOUNDS: [(&str, NumberWord); 23] = [
+    ("ten", NumberWord::Teen(10)),
+    ("eleven", NumberWord::Teen(11)),
+    ("twelve", NumberWord::Teen(12)),
+    ("thirteen", NumberWord::Teen(13)),
+    ("fourteen", NumberWord::Teen(14)),
+    ("fifteen", NumberWord::Teen(15)),
+    ("sixteen", NumberWord::Teen(16)),
+    ("seventeen", NumberWord::Teen(17)),
+    ("eighteen", NumberWord::Teen(18)),
+    ("nineteen", NumberWord::Teen(19)),
+    ("twenty", NumberWord::Ten(20)),
+    ("thirty", NumberWord::Ten(30)),
+    ("forty", NumberWord::Ten(40)),
+    ("fifty", NumberWord::Ten(50)),
+    ("sixty", NumberWord::Ten(60)),
+    ("seventy", NumberWord::Ten(70)),
+    ("eighty", NumberWord::Ten(80)),
+    ("ninety", NumberWord::Ten(90)),
+    ("hundred", NumberWord::Hundred),
+    ("thousand", NumberWord::Scale(1_000)),
+    ("million", NumberWord::Scale(1_000_000)),
+    ("billion", NumberWord::Scale(1_000_000_000)),
+    ("and", NumberWord::And),
+];
+const SPANISH: [Option<&str>; 10] = [
+    Some("cero"),
+    Some("uno"),
+    Some("dos"),
+    Some("tres"),
+    Some("cuatro"),
+    Some("cinco"),
+    Some("seis"),
+    Some("siete"),
+    Some("ocho"),
+    Some("nueve"),
+];
+const GERMAN: [Option<&str>; 10] = [
+    Some("null"),
+    Some("eins"),
+    Some("zwei"),
+    Some("drei"),
+    Some("vier"),
+    Some("fünf"),
+    Some("sechs"),
+    Some("sieben"),
+    Some("acht"),
+    Some("neun"),
+];
+const FRENCH: [Option<&str>; 10] = [
+    Some("zéro"),
+    Some("un"),
+    Some("deux"),
+    Some("trois"),
+    Some("quatre"),
+    Some("cinq"),
+    Some("six"),
+    Some("sept"),
+    Some("huit"),
+    Some("neuf"),
+];
+const ROMAN: [Option<&str>; 10] = [
+    None,
+    Some("I"),
+    Some("II"),
+    Some("III"),
+    Some("IV"),
+    Some("V"),
+    Some("VI"),
+    Some("VII"),
+    Some("VIII"),
+    Some("IX"),
+];
+
+/// The words (and numerals) which count as a digit in a calibration document.
+///
+/// Every vocabulary includes the numerals 1 to 9 in every script (see `decimal_value`),
+/// zero (numeral and word) is only included on request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitVocabulary {
+    words: Vec<(String, u32)>,
+    // the word `with_zero` adds, if the language has one
+    zero: Option<&'static str>,
+    // words only match when not part of a longer run of letters
+    whole_words: bool,
+    // words for numbers past nine, only read by `DigitExtractor::numbers`
+    compounds: &'static [(&'static str, NumberWord)],
+}
+
+impl DigitVocabulary {
+    /// Only the numerals, no words
+    pub fn numerals() -> Self {
+        DigitVocabulary {
+            words: (1..10).flat_map(numerals).collect(),
+            zero: None,
+            whole_words: false,
+            compounds: &[],
+        }
+    }
+
+    fn builtin(words: &[Option<&'static str>; 10]) -> Self {
+        let mut vocabulary = Self::numerals();
+        vocabulary.zero = words[0];
+        for (d, word) in words.iter().enumerate().skip(1) {
+            if let Some(word) = word {
+                vocabulary = vocabulary.with_word(*word, d as u32);
+            }
+        }
+        vocabulary
+    }
+
+    /// "one" to "nine", the puzzle's own vocabulary, along with the words
+    /// `DigitExtractor::numbers` needs for larger numbers ("twelve", "hundred")
+    pub fn english() -> Self {
+        DigitVocabulary {
+            compounds: &ENGLISH_COMPOUNDS,
+            ..Self::builtin(&ENGLISH)
+        }
+    }
+
+    pub fn spanish() -> Self {
+        Self::builtin(&SPANISH)
+    }
+
+    pub fn german() -> Self {
+        Self::builtin(&GERMAN)
+    }
+
+    pub fn french() -> Self {
+        Self::builtin(&FRENCH)
+    }
+
+    /// "I" to "IX", matched as whole words so "VIII" is 8 rather than "V" then "I"s
+    pub fn roman() -> Self {
+        Self::builtin(&ROMAN).with_whole_words()
+    }
+
+    /// A built in vocabulary by (lowercase) name
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "numerals" => Some(Self::numerals()),
+            "english" => Some(Self::english()),
+            "spanish" => Some(Self::spanish()),
+            "german" => Some(Self::german()),
+            "french" => Some(Self::french()),
+            "roman" => Some(Self::roman()),
+            _ => None,
+        }
+    }
 
-    if digits.len() == 0 {
-        return None;
+    /// Reads a vocabulary from a file, see `from_str` for the format
+    #[cfg(feature = "std")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {e}", path.display()))?
+            .parse()
     }
 
-    let first = digits[0].to_string();
-    let last = digits[digits.len() - 1];
+    /// Counts `word` as the digit `value`
+    pub fn with_word(mut self, word: impl Into<String>, value: u32) -> Self {
+        assert!(value < 10, "{value} is not a single digit");
+        self.words.push((word.into(), value));
+        self
+    }
 
-    let concated = first + &last;
-    u32::from_str_radix(&concated, 10).ok()
+    /// Only counts a word where it isn't part of a longer run of ASCII letters,
+    /// numerals still match anywhere
+    pub fn with_whole_words(mut self) -> Self {
+        self.whole_words = true;
+        self
+    }
+
+    /// Counts the numeral 0, along with the language's word for zero if it has one
+    pub fn with_zero(mut self) -> Self {
+        self.words.extend(numerals(0));
+        match self.zero {
+            Some(zero) => self.with_word(zero, 0),
+            None => self,
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words.iter().map(|(w, d)| (w.as_str(), *d))
+    }
+
+    fn words_for(&self, mode: ParseMode) -> Vec<&(String, u32)> {
+        self.words
+            .iter()
+            .filter(|(word, _)| {
+                let numeral = is_numeral(word);
+                match mode {
+                    ParseMode::Digits => numeral,
+                    ParseMode::Words => !numeral,
+                    ParseMode::DigitsAndWords | ParseMode::CaseInsensitive => true,
+                }
+            })
+            .collect()
+    }
+
+    fn scanner(&self, mode: ParseMode) -> DigitScanner {
+        let words = self.words_for(mode);
+        let patterns = words.iter().map(|(word, _)| word);
+        DigitScanner {
+            automaton: match mode {
+                ParseMode::CaseInsensitive => AhoCorasick::ascii_case_insensitive(patterns),
+                _ => AhoCorasick::new(patterns),
+            },
+            values: words.iter().map(|(_, d)| *d).collect(),
+            whole_words: self.whole_words,
+        }
+    }
 }
 
-const DIGIT_MATCHES: [&str; 18] = [
-    "one", "1", "two", "2", "three", "3", "four", "4", "five", "5", "six", "6", "seven", "7",
-    "eight", "8", "nine", "9",
-];
+impl Default for DigitVocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// One `word digit` pair per line, blank lines and lines starting with `#` are ignored.
+/// The numerals 1 to 9 of every script are always included.
+impl FromStr for DigitVocabulary {
+    type Err = String;
 
-fn into_digit(digit: &str) -> &str {
-    match digit {
-        "one" => "1",
-        "two" => "2",
-        "three" => "3",
-        "four" => "4",
-        "five" => "5",
-        "six" => "6",
-        "seven" => "7",
-        "eight" => "8",
-        "nine" => "9",
-        other => other,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut vocabulary = Self::numerals();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, digit) = line
+                .rsplit_once(char::is_whitespace)
+                .ok_or_else(|| format!("Expected 'word digit' on line {}", i + 1))?;
+            let digit: u32 = digit
+                .parse()
+                .ok()
+                .filter(|d| *d < 10)
+                .ok_or_else(|| format!("Invalid digit '{digit}' on line {}", i + 1))?;
+            vocabulary = vocabulary.with_word(word.trim_end(), digit);
+        }
+        Ok(vocabulary)
     }
 }
 
-fn parse_line(line: &str) -> Option<u32> {
-    // Note this method only works because none of our needles are substrings of the others, and none start and begin with the same letter
-    let digits_found: Vec<((usize, &str), (usize, &str))> = DIGIT_MATCHES
-        .iter()
-        .filter_map(|needle| {
-            let mut digits = line.match_indices(needle).peekable();
+/// What counts as a digit when reading a calibration line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Only numerals, as in part 1
+    Digits,
+    /// Numerals and words, as in part 2
+    #[default]
+    DigitsAndWords,
+    /// Only words, numerals are ignored
+    Words,
+    /// Numerals and words, ignoring the case of ASCII letters ("One", "TWO")
+    CaseInsensitive,
+}
 
-            let first: Option<(usize, &str)> = digits.peek().copied();
-            first.zip(digits.last())
-        })
-        .collect();
+// A vocabulary compiled for searching
+struct DigitScanner {
+    automaton: AhoCorasick,
+    // the digit for each of the automaton's patterns
+    values: Vec<u32>,
+    whole_words: bool,
+}
 
-    if digits_found.len() == 0 {
-        return None;
+impl DigitScanner {
+    fn accepts(&self, line: &[u8], m: &Match) -> bool {
+        !self.whole_words || is_whole_word(line, m.start, m.end)
     }
 
-    let mut first = digits_found[0].0;
-    let mut last = digits_found[0].1;
+    fn digit<'a>(&self, line: &'a str, m: Match) -> Digit<'a> {
+        Digit {
+            text: &line[m.start..m.end],
+            value: self.values[m.pattern],
+            start: m.start,
+            end: m.end,
+        }
+    }
+}
+
+/// A digit found in a line, at `line[start..end]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digit<'a> {
+    pub text: &'a str,
+    pub value: u32,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Display for Digit<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "'{}' at {}..{}", self.text, self.start, self.end)
+    }
+}
+
+/// The first and last digit of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCalibration<'a> {
+    /// Counting from 1
+    pub line: usize,
+    pub first: Digit<'a>,
+    pub last: Digit<'a>,
+}
+
+impl LineCalibration<'_> {
+    pub fn value(&self) -> u32 {
+        self.first.value * 10 + self.last.value
+    }
+}
+
+/// What to do with a line without any digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strictness {
+    /// Leave it out of the sum
+    Lenient,
+    /// Fail the whole document
+    #[default]
+    Strict,
+}
+
+/// Every line of a calibration document, either with its digits or rejected
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CalibrationReport<'a> {
+    pub lines: Vec<LineCalibration<'a>>,
+    /// Line numbers, counting from 1, of the lines without digits
+    pub rejected: Vec<usize>,
+}
 
-    for (d_first, d_last) in &digits_found[1..] {
-        if d_first.0 < first.0 {
-            first = *d_first
+impl CalibrationReport<'_> {
+    pub fn sum(&self) -> u32 {
+        self.lines.iter().map(LineCalibration::value).sum()
+    }
+}
+
+impl Display for CalibrationReport<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for line in &self.lines {
+            writeln!(
+                f,
+                "line {}: {} and {} = {}",
+                line.line,
+                line.first,
+                line.last,
+                line.value()
+            )?;
         }
-        if d_last.0 > last.0 {
-            last = *d_last;
+        if !self.rejected.is_empty() {
+            writeln!(f, "rejected lines: {}", join(&self.rejected))?;
         }
+        write!(f, "sum: {}", self.sum())
     }
+}
+
+fn join(line_numbers: &[usize]) -> String {
+    line_numbers
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    let concated = into_digit(first.1).to_string() + into_digit(last.1);
-    u32::from_str_radix(&concated, 10).ok()
+fn read_line<'a>(digits: &DigitScanner, line: &'a str) -> Option<(Digit<'a>, Digit<'a>)> {
+    let bytes = line.as_bytes();
+    let (first, last) = digits
+        .automaton
+        .first_and_last_where(bytes, |m| digits.accepts(bytes, m))?;
+    Some((digits.digit(line, first), digits.digit(line, last)))
 }
 
-fn map_calibration_sum(calibration_doc: &str) -> u32 {
+fn parse_line(digits: &DigitScanner, line: &str) -> Option<u32> {
+    let (first, last) = read_line(digits, line)?;
+
+    Some(first.value * 10 + last.value)
+}
+
+/// Reads the first and last digit of every line.
+/// When `Strict` any line without digits is an error naming every such line.
+pub fn calibration_report<'a>(
+    calibration_doc: &'a str,
+    vocabulary: &DigitVocabulary,
+    mode: ParseMode,
+    strictness: Strictness,
+) -> Result<CalibrationReport<'a>, String> {
+    let digits = vocabulary.scanner(mode);
+    let mut report = CalibrationReport::default();
+
+    for (i, line) in calibration_doc.lines().enumerate() {
+        match read_line(&digits, line) {
+            Some((first, last)) => report.lines.push(LineCalibration {
+                line: i + 1,
+                first,
+                last,
+            }),
+            None => report.rejected.push(i + 1),
+        }
+    }
+
+    if strictness == Strictness::Strict && !report.rejected.is_empty() {
+        return Err(format!("No digits on lines {}", join(&report.rejected)));
+    }
+    Ok(report)
+}
+
+/// The sum of every line's calibration value, failing on the first line without digits
+pub fn map_calibration_sum(
+    calibration_doc: &str,
+    vocabulary: &DigitVocabulary,
+    mode: ParseMode,
+) -> Result<u32, String> {
+    let digits = vocabulary.scanner(mode);
     calibration_doc
         .lines()
-        .map(|l| parse_line(l))
-        .sum::<Option<u32>>()
-        .unwrap()
+        .enumerate()
+        .map(|(i, line)| {
+            parse_line(&digits, line).ok_or_else(|| format!("No digits on line {}", i + 1))
+        })
+        .sum()
+}
+
+/// A vocabulary laid out for reading lines as bytes, checking for a digit at each position
+/// from the front for the first and from the back for the last. Once built nothing allocates,
+/// and the results are the same as `map_calibration_sum`.
+#[derive(Debug, Clone)]
+pub struct ByteScanner {
+    // the value + 1 of the single byte word for each byte, 0 for none
+    single: [u8; 256],
+    // the rest of the longer words starting with each byte, longest first
+    longer: [Vec<(Vec<u8>, u32)>; 256],
+    fold_case: bool,
+    whole_words: bool,
+}
+
+impl ByteScanner {
+    pub fn new(vocabulary: &DigitVocabulary, mode: ParseMode) -> Self {
+        let fold_case = mode == ParseMode::CaseInsensitive;
+        let mut single = [0; 256];
+        let mut longer: [Vec<(Vec<u8>, u32)>; 256] = core::array::from_fn(|_| Vec::new());
+
+        for (word, value) in vocabulary.words_for(mode) {
+            let Some((&first, rest)) = word.as_bytes().split_first() else {
+                continue;
+            };
+            let firsts = if fold_case && first.is_ascii_alphabetic() {
+                [first.to_ascii_lowercase(), first.to_ascii_uppercase()]
+            } else {
+                [first, first]
+            };
+            for first in firsts {
+                if rest.is_empty() {
+                    single[first as usize] = *value as u8 + 1;
+                } else {
+                    let rest = if fold_case {
+                        rest.to_ascii_lowercase()
+                    } else {
+                        rest.to_vec()
+                    };
+                    longer[first as usize].push((rest, *value));
+                }
+            }
+        }
+        for words in &mut longer {
+            words.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+            words.dedup_by(|a, b| a.0 == b.0);
+        }
+        ByteScanner {
+            single,
+            longer,
+            fold_case,
+            whole_words: vocabulary.whole_words,
+        }
+    }
+
+    // The digit starting at `line[i]`, the longest if several do
+    #[inline]
+    fn digit_at(&self, line: &[u8], i: usize) -> Option<u32> {
+        let first = line[i] as usize;
+        let rest = &line[i + 1..];
+        for (word, value) in &self.longer[first] {
+            let matched = match rest.get(..word.len()) {
+                Some(start) if self.fold_case => start.eq_ignore_ascii_case(word),
+                Some(start) => start == word.as_slice(),
+                None => false,
+            };
+            if matched && (!self.whole_words || is_whole_word(line, i, i + 1 + word.len())) {
+                return Some(*value);
+            }
+        }
+        if self.whole_words && !is_whole_word(line, i, i + 1) {
+            return None;
+        }
+        self.single[first].checked_sub(1).map(u32::from)
+    }
+
+    pub fn parse_line(&self, line: &[u8]) -> Option<u32> {
+        let first = (0..line.len()).find_map(|i| self.digit_at(line, i))?;
+        let last = (0..line.len()).rev().find_map(|i| self.digit_at(line, i))?;
+        Some(first * 10 + last)
+    }
+
+    /// Fails with the (1 based) number of the first line without a digit.
+    /// Lines end as in `str::lines`, at `\n` or `\r\n`.
+    pub fn calibration_sum(&self, calibration_doc: &[u8]) -> Result<u64, usize> {
+        let mut sum = 0;
+        let mut rest = calibration_doc;
+        let mut line_number: usize = 0;
+        while !rest.is_empty() {
+            line_number += 1;
+            let end = find_byte(b'\n', rest).unwrap_or(rest.len());
+            let line = &rest[..end];
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            sum += u64::from(self.parse_line(line).ok_or(line_number)?);
+            rest = rest.get(end + 1..).unwrap_or_default();
+        }
+        Ok(sum)
+    }
+}
+
+// A word (starting with a letter) at `line[start..end]` without letters either side,
+// anything else is a numeral and always whole
+fn is_whole_word(line: &[u8], start: usize, end: usize) -> bool {
+    !line[start].is_ascii_alphabetic()
+        || !(line[..start].last().is_some_and(u8::is_ascii_alphabetic)
+            || line.get(end).is_some_and(u8::is_ascii_alphabetic))
+}
+
+// Checks 8 bytes at a time, a zero byte in `x ^ needle` is a match
+fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    const LO: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HI: u64 = u64::from_ne_bytes([0x80; 8]);
+    let repeated = LO * u64::from(needle);
+
+    let mut chunks = haystack.chunks_exact(8);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let x = u64::from_le_bytes(chunk.try_into().unwrap()) ^ repeated;
+        // only ever wrong above the first zero byte, which is all that's needed
+        let zeros = x.wrapping_sub(LO) & !x & HI;
+        if zeros != 0 {
+            return Some(i * 8 + zeros.trailing_zeros() as usize / 8);
+        }
+    }
+    let tail = chunks.remainder();
+    tail.iter()
+        .position(|b| *b == needle)
+        .map(|i| haystack.len() - tail.len() + i)
+}
+
+/// A calibration document of roughly `len` bytes, every line having at least one digit,
+/// for the tests and `examples/day1_bench.rs`
+#[doc(hidden)]
+pub fn generate_document(rng: &mut XorShift, len: usize) -> String {
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut doc = String::with_capacity(len + 64);
+
+    while doc.len() < len {
+        let mut has_digit = false;
+        for _ in 0..rng.below(12) + 2 {
+            if rng.one_in(5) {
+                let word = *rng.pick(&WORDS);
+                doc.push_str(word);
+            } else if rng.one_in(8) {
+                doc.push(char::from(b'1' + rng.below(9) as u8));
+                has_digit = true;
+            } else {
+                doc.push(char::from(b'a' + rng.below(26) as u8));
+            }
+        }
+        if !has_digit {
+            doc.push(char::from(b'1' + rng.below(9) as u8));
+        }
+        doc.push('\n');
+    }
+    doc
+}
+
+/// Which of a line's digits make up its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Take {
+    /// The first `k` followed by the last `k`, a line with `2k` or fewer uses each of
+    /// its digits once. A calibration value is `Ends(1)` on lines with two or more digits.
+    Ends(usize),
+    All,
+}
+
+/// Pulls digits, or whole numbers, out of lines of free-form text
+pub struct DigitExtractor {
+    digits: DigitScanner,
+    // the vocabulary's words, for reading whole numbers
+    words: Vec<(String, u32)>,
+    compounds: &'static [(&'static str, NumberWord)],
+    numerals: bool,
+    fold_case: bool,
+}
+
+impl DigitExtractor {
+    pub fn new(vocabulary: &DigitVocabulary, mode: ParseMode) -> Self {
+        DigitExtractor {
+            digits: vocabulary.scanner(mode),
+            words: vocabulary
+                .words_for(mode)
+                .into_iter()
+                .filter(|(word, _)| !is_numeral(word))
+                .cloned()
+                .collect(),
+            compounds: match mode {
+                ParseMode::Digits => &[],
+                _ => vocabulary.compounds,
+            },
+            numerals: mode != ParseMode::Words,
+            fold_case: mode == ParseMode::CaseInsensitive,
+        }
+    }
+
+    /// Every digit in the line in order. Overlapping digits ("twone") each count,
+    /// but of those starting at the same place only the longest does.
+    pub fn digits<'a>(&self, line: &'a str) -> Vec<Digit<'a>> {
+        let bytes = line.as_bytes();
+        let mut matches: Vec<Match> = self
+            .digits
+            .automaton
+            .find_overlapping(bytes)
+            .filter(|m| self.digits.accepts(bytes, m))
+            .collect();
+        matches.sort_by_key(|m| (m.start, Reverse(m.len())));
+        matches.dedup_by_key(|m| m.start);
+
+        matches
+            .into_iter()
+            .map(|m| self.digits.digit(line, m))
+            .collect()
+    }
+
+    /// With `Ends(k)` a line with `2k` or fewer digits uses each of its digits once
+    pub fn take<'a>(&self, line: &'a str, take: Take) -> Vec<Digit<'a>> {
+        let digits = self.digits(line);
+        match take {
+            Take::All => digits,
+            Take::Ends(k) => {
+                let first = k.min(digits.len());
+                let last = k.min(digits.len() - first);
+                let mut ends = digits[..first].to_vec();
+                ends.extend_from_slice(&digits[digits.len() - last..]);
+                ends
+            }
+        }
+    }
+
+    /// The taken digits read as one number, `None` if there aren't any or they don't fit
+    pub fn value<N: Num>(&self, line: &str, take: Take) -> Option<N> {
+        let digits = self.take(line, take);
+        if digits.is_empty() {
+            return None;
+        }
+
+        digits.iter().try_fold(N::ZERO, |acc, d| {
+            acc.checked_mul(N::from_usize(10))?
+                .checked_add(N::from_usize(d.value as usize))
+        })
+    }
+
+    // A whole word of the vocabulary as part of a number
+    fn number_word(&self, word: &str) -> Option<NumberWord> {
+        let same = |w: &str| {
+            if self.fold_case {
+                w.eq_ignore_ascii_case(word)
+            } else {
+                w == word
+            }
+        };
+        if let Some((_, d)) = self.words.iter().find(|(w, _)| same(w)) {
+            return Some(match d {
+                0 => NumberWord::Zero,
+                d => NumberWord::Unit(u64::from(*d)),
+            });
+        }
+        self.compounds
+            .iter()
+            .find(|(w, _)| same(w))
+            .map(|(_, word)| *word)
+    }
+
+    /// Every whole number in `text`, runs of numerals or of the vocabulary's words.
+    /// Number words run together when only spaces or hyphens separate them
+    /// ("one hundred and twenty-one"), which takes the larger English words.
+    pub fn numbers<'a>(&self, text: &'a str) -> Vec<Number<'a>> {
+        let tokens = tokens(text);
+        // only spaces and hyphens between two tokens
+        let joined = |l: usize, r: usize| {
+            let gap = &text[tokens[l].end..tokens[r].start];
+            gap.bytes().all(|b| b == b' ' || b == b'-')
+        };
+
+        let mut numbers = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if let Some(value) = numeral_value(tokens[i].text) {
+                if self.numerals {
+                    numbers.push(Number {
+                        text: tokens[i].text,
+                        value,
+                        start: tokens[i].start,
+                        end: tokens[i].end,
+                    });
+                }
+                i += 1;
+                continue;
+            }
+
+            let mut compound = Compound::default();
+            let mut end = i;
+            while end < tokens.len() && (end == i || joined(end - 1, end)) {
+                match self.number_word(tokens[end].text) {
+                    // "and" only counts when followed by more of the number
+                    Some(NumberWord::And) if end > i => {
+                        let next = end + 1;
+                        let continued = next < tokens.len()
+                            && joined(end, next)
+                            && matches!(
+                                compound.last,
+                                Some(NumberWord::Hundred | NumberWord::Scale(_))
+                            )
+                            && self
+                                .number_word(tokens[next].text)
+                                .is_some_and(|w| compound.push(w));
+                        if !continued {
+                            break;
+                        }
+                        end += 2;
+                    }
+                    Some(word) if word != NumberWord::And && compound.push(word) => end += 1,
+                    _ => break,
+                }
+            }
+
+            if end > i {
+                let (start, stop) = (tokens[i].start, tokens[end - 1].end);
+                numbers.push(Number {
+                    text: &text[start..stop],
+                    value: compound.value(),
+                    start,
+                    end: stop,
+                });
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        numbers
+    }
+}
+
+/// A whole number in a line, in numerals ("21") or words ("twenty-one")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number<'a> {
+    pub text: &'a str,
+    pub value: u64,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberWord {
+    Zero,
+    Unit(u64),
+    Teen(u64),
+    Ten(u64),
+    Hundred,
+    Scale(u64),
+    And,
+}
+
+// The value of a run of number words so far
+#[derive(Debug, Clone, Copy, Default)]
+struct Compound {
+    total: u64,
+    // below the last scale word
+    current: u64,
+    last: Option<NumberWord>,
+    scale: Option<u64>,
+}
+
+impl Compound {
+    // Whether `word` carries on this number ("twenty" then "one"), if not nothing changes
+    fn push(&mut self, word: NumberWord) -> bool {
+        use NumberWord::*;
+        let continues = match (self.last, word) {
+            (None, Zero | Unit(_) | Teen(_) | Ten(_)) => true,
+            (Some(Ten(_)), Unit(_)) => true,
+            (Some(Hundred | Scale(_)), Unit(_) | Teen(_) | Ten(_)) => true,
+            (Some(Unit(_) | Teen(_) | Ten(_)), Hundred) => self.current < 100,
+            (Some(Unit(_) | Teen(_) | Ten(_) | Hundred), Scale(s)) => {
+                self.scale.is_none_or(|larger| s < larger)
+            }
+            _ => false,
+        };
+        if !continues {
+            return false;
+        }
+
+        match word {
+            Unit(v) | Teen(v) | Ten(v) => self.current += v,
+            Hundred => self.current *= 100,
+            Scale(s) => {
+                self.total += self.current * s;
+                self.current = 0;
+                self.scale = Some(s);
+            }
+            Zero | And => {}
+        }
+        self.last = Some(word);
+        true
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+// A run of letters or of ASCII digits, at `text[start..end]`
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+fn tokens(text: &str) -> Vec<Token<'_>> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut prev: Option<(usize, bool)> = None;
+    for (i, c) in text.char_indices() {
+        let class = if decimal_value(c).is_some() {
+            Some(true)
+        } else if c.is_alphabetic() {
+            Some(false)
+        } else {
+            None
+        };
+        match (prev, class) {
+            (Some((_, p)), Some(c)) if p == c => {}
+            (Some((start, _)), _) => {
+                tokens.push(Token {
+                    text: &text[start..i],
+                    start,
+                    end: i,
+                });
+                prev = class.map(|c| (i, c));
+            }
+            (None, _) => prev = class.map(|c| (i, c)),
+        }
+    }
+    if let Some((start, _)) = prev {
+        tokens.push(Token {
+            text: &text[start..],
+            start,
+            end: text.len(),
+        });
+    }
+    tokens
+}
+
+// A run of decimal digits read as one number, `None` if it doesn't fit
+fn numeral_value(numeral: &str) -> Option<u64> {
+    numeral.chars().try_fold(0u64, |acc, c| {
+        acc.checked_mul(10)?
+            .checked_add(u64::from(decimal_value(c)?))
+    })
 }
 
 #[cfg(test)]
@@ -90,7 +942,10 @@ a1b2c3d4e5f
 treb7uchet
 "##;
 
-        assert_eq!(map_calibration_sum(input), 142);
+        assert_eq!(
+            map_calibration_sum(input, &DigitVocabulary::english(), ParseMode::Digits).unwrap(),
+            142
+        );
     }
 
     #[test]
@@ -104,7 +959,15 @@ zoneight234
 7pqrstsixteen
 "##;
 
-        assert_eq!(map_calibration_sum(input), 281);
+        assert_eq!(
+            map_calibration_sum(
+                input,
+                &DigitVocabulary::english(),
+                ParseMode::DigitsAndWords
+            )
+            .unwrap(),
+            281
+        );
     }
 
     #[test]
@@ -113,6 +976,350 @@ zoneight234
 4fsdfjgj68fiveight
 "##;
         // 21 + 48 = 69
-        assert_eq!(map_calibration_sum(input), 69);
+        assert_eq!(
+            map_calibration_sum(
+                input,
+                &DigitVocabulary::english(),
+                ParseMode::DigitsAndWords
+            )
+            .unwrap(),
+            69
+        );
+    }
+
+    #[test]
+    fn words_sharing_letters() {
+        let digits = DigitVocabulary::english().scanner(ParseMode::DigitsAndWords);
+        assert_eq!(parse_line(&digits, "sevenineightwo"), Some(72));
+        assert_eq!(parse_line(&digits, "oneoneone"), Some(11));
+        assert_eq!(parse_line(&digits, "eeeightt"), Some(88));
+        assert_eq!(parse_line(&digits, "abc"), None);
+    }
+
+    #[test]
+    fn report_positions() {
+        let input = "two1nine\nnothing here\n7pqrstsixteen\n\n";
+        let english = DigitVocabulary::english();
+        let report = calibration_report(
+            input,
+            &english,
+            ParseMode::DigitsAndWords,
+            Strictness::Lenient,
+        )
+        .unwrap();
+
+        assert_eq!(report.rejected, [2, 4]);
+        assert_eq!(report.sum(), 29 + 76);
+        let first = report.lines[1].first;
+        let last = report.lines[1].last;
+        assert_eq!((first.text, first.value, first.start), ("7", 7, 0));
+        assert_eq!((last.text, last.start, last.end), ("six", 6, 9));
+        assert_eq!(
+            report.to_string(),
+            "line 1: 'two' at 0..3 and 'nine' at 4..8 = 29
+line 3: '7' at 0..1 and 'six' at 6..9 = 76
+rejected lines: 2, 4
+sum: 105"
+        );
+
+        assert_eq!(
+            calibration_report(
+                input,
+                &english,
+                ParseMode::DigitsAndWords,
+                Strictness::Strict
+            ),
+            Err("No digits on lines 2, 4".to_string())
+        );
+    }
+
+    #[test]
+    fn take_first_and_last_k() {
+        let english = DigitVocabulary::english();
+        let extractor = DigitExtractor::new(&english, ParseMode::DigitsAndWords);
+        let line = "a1twone3fourxfive6";
+
+        let all: Vec<_> = extractor.digits(line).iter().map(|d| d.value).collect();
+        assert_eq!(all, [1, 2, 1, 3, 4, 5, 6]);
+        assert_eq!(extractor.value::<u64>(line, Take::All), Some(1213456));
+        assert_eq!(extractor.value::<u32>(line, Take::Ends(2)), Some(1256));
+        assert_eq!(extractor.value::<u32>("x7x", Take::Ends(3)), Some(7));
+        assert_eq!(extractor.value::<u32>("1x2x3", Take::Ends(2)), Some(123));
+        assert_eq!(extractor.value::<u32>("nothing", Take::All), None);
+        assert_eq!(extractor.value::<u8>("2five6", Take::All), None);
+        assert_eq!(extractor.value::<u8>("2five5", Take::All), Some(255));
+
+        // the calibration value is the first and last digit
+        let digits = english.scanner(ParseMode::DigitsAndWords);
+        for line in ["two1nine", "zoneight234", "twone", "eighthree"] {
+            assert_eq!(
+                extractor.value::<u32>(line, Take::Ends(1)),
+                parse_line(&digits, line)
+            );
+        }
+    }
+
+    #[test]
+    fn byte_scanner_matches_parse_line() {
+        let mut rng = XorShift::new(2023);
+        let doc = generate_document(&mut rng, 20_000);
+        let mixed_case = doc.replace("two", "TwO").replace("eight", "EIGHT");
+
+        for vocabulary in [
+            DigitVocabulary::english(),
+            DigitVocabulary::roman().with_zero(),
+        ] {
+            for mode in [
+                ParseMode::Digits,
+                ParseMode::DigitsAndWords,
+                ParseMode::Words,
+                ParseMode::CaseInsensitive,
+            ] {
+                let digits = vocabulary.scanner(mode);
+                let bytes = ByteScanner::new(&vocabulary, mode);
+                for line in mixed_case.lines().chain(["", "twone", "VIII", "oNeIGHT"]) {
+                    assert_eq!(
+                        bytes.parse_line(line.as_bytes()),
+                        parse_line(&digits, line),
+                        "{line} {mode:?}"
+                    );
+                }
+            }
+        }
+
+        let english = DigitVocabulary::english();
+        let bytes = ByteScanner::new(&english, ParseMode::DigitsAndWords);
+        assert_eq!(
+            bytes.calibration_sum(doc.as_bytes()),
+            Ok(
+                map_calibration_sum(&doc, &english, ParseMode::DigitsAndWords)
+                    .unwrap()
+                    .into()
+            )
+        );
+        assert_eq!(bytes.calibration_sum(b"1\r\ntwo\r\nxx\n3"), Err(3));
+        assert_eq!(bytes.calibration_sum(b"1\r\ntwo\n"), Ok(11 + 22));
+        assert_eq!(bytes.calibration_sum(b"\n"), Err(1));
+        assert_eq!(bytes.calibration_sum(b""), Ok(0));
+
+        let haystack = b"abcdefgh\nijklmnopqrstuvw\n";
+        assert_eq!(find_byte(b'\n', haystack), Some(8));
+        assert_eq!(find_byte(b'\n', &haystack[9..]), Some(15));
+        assert_eq!(find_byte(b'z', haystack), None);
+        assert_eq!(find_byte(0x80, &[0x81, 0x00, 0x80]), Some(2));
+    }
+
+    #[test]
+    fn decimal_digits_of_every_script() {
+        for (script, zero) in [
+            ("ASCII", '0'),
+            ("Arabic-Indic", '٠'),
+            ("Extended Arabic-Indic", '۰'),
+            ("Devanagari", '०'),
+            ("Bengali", '০'),
+            ("Thai", '๐'),
+            ("Fullwidth", '０'),
+            ("Mathematical bold", '𝟎'),
+        ] {
+            for d in 0..10 {
+                let c = char::from_u32(zero as u32 + d).unwrap();
+                assert_eq!(decimal_value(c), Some(d), "{script} {c}");
+            }
+        }
+
+        // numeric, but not decimal digits
+        for c in ['½', 'Ⅻ', '²', '①', '〇', 'a', '/'] {
+            assert_eq!(decimal_value(c), None, "{c}");
+        }
+    }
+
+    #[test]
+    fn calibration_in_other_scripts() {
+        let numerals = DigitVocabulary::numerals();
+        let sum = |input| map_calibration_sum(input, &numerals, ParseMode::Digits).unwrap();
+
+        // Arabic-Indic, Devanagari, fullwidth, then mixed with ASCII
+        assert_eq!(sum("x٣y٤z٧"), 37);
+        assert_eq!(sum("पृष्ठ ४२"), 42);
+        assert_eq!(sum("ＡＢ１ＣＤ９"), 19);
+        assert_eq!(sum("1 ٢ ३ ４ 5"), 15);
+        // '½' isn't a digit, and zero is only counted on request
+        assert_eq!(sum("½ 8 ٠"), 88);
+        assert_eq!(
+            map_calibration_sum("٠ 8", &numerals.clone().with_zero(), ParseMode::Digits).unwrap(),
+            8
+        );
+
+        let bytes = ByteScanner::new(&numerals, ParseMode::Digits);
+        assert_eq!(bytes.parse_line("x٣y٤z٧".as_bytes()), Some(37));
+        assert_eq!(bytes.parse_line("½".as_bytes()), None);
+
+        let english = DigitVocabulary::english();
+        let extractor = DigitExtractor::new(&english, ParseMode::DigitsAndWords);
+        assert_eq!(
+            extractor.value::<u32>("one ٢ three ४", Take::All),
+            Some(1234)
+        );
+        assert_eq!(extractor.numbers("retried ٣ times")[0].value, 3);
+        assert_eq!(extractor.numbers("page ４２")[0].value, 42);
+    }
+
+    #[test]
+    fn compound_numbers() {
+        let english = DigitVocabulary::english().with_zero();
+        let extractor = DigitExtractor::new(&english, ParseMode::CaseInsensitive);
+        let values =
+            |text| -> Vec<u64> { extractor.numbers(text).iter().map(|n| n.value).collect() };
+
+        assert_eq!(values("twelve"), [12]);
+        assert_eq!(values("Twenty-one"), [21]);
+        assert_eq!(values("one hundred"), [100]);
+        assert_eq!(values("one thousand two hundred and five"), [1205]);
+        assert_eq!(values("nineteen hundred and eighty-four"), [1984]);
+        assert_eq!(values("two million three thousand"), [2_003_000]);
+        // words not forming one number are separate ones
+        assert_eq!(values("one two, three and four"), [1, 2, 3, 4]);
+        assert_eq!(values("twenty twenty"), [20, 20]);
+        assert_eq!(values("zero one"), [0, 1]);
+        // whole words only
+        assert_eq!(values("someone sent tension"), []);
+
+        let log = extractor.numbers("retried 3 times, gave up after forty-two seconds");
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].value, 3);
+        assert_eq!(
+            (log[1].text, log[1].start, log[1].end),
+            ("forty-two", 31, 40)
+        );
+
+        // the vocabulary's own words, cased as it has them unless ignoring case
+        let spanish = DigitVocabulary::spanish();
+        let extractor = DigitExtractor::new(&spanish, ParseMode::DigitsAndWords);
+        let values: Vec<_> = extractor
+            .numbers("dos, 12 y Tres")
+            .iter()
+            .map(|n| n.value)
+            .collect();
+        assert_eq!(values, [2, 12]);
+        let extractor = DigitExtractor::new(&english, ParseMode::Words);
+        let values: Vec<_> = extractor
+            .numbers("7 twenty-one")
+            .iter()
+            .map(|n| n.value)
+            .collect();
+        assert_eq!(values, [21]);
+    }
+
+    #[test]
+    fn modes() {
+        let english = DigitVocabulary::english();
+        let sum = |input, mode| map_calibration_sum(input, &english, mode).unwrap();
+        assert_eq!(
+            map_calibration_sum("1\nx", &english, ParseMode::Digits),
+            Err("No digits on line 2".to_string())
+        );
+
+        assert_eq!(sum("two1nine", ParseMode::Digits), 11);
+        assert_eq!(sum("two1nine", ParseMode::DigitsAndWords), 29);
+        assert_eq!(sum("3two1nine4", ParseMode::Words), 29);
+        assert_eq!(sum("Two1NINE", ParseMode::DigitsAndWords), 11);
+        assert_eq!(sum("Two1NINE", ParseMode::CaseInsensitive), 29);
+    }
+
+    #[test]
+    fn builtin_languages() {
+        let spanish = DigitVocabulary::spanish();
+        assert_eq!(
+            map_calibration_sum("xunotresdosx\nocho", &spanish, ParseMode::DigitsAndWords).unwrap(),
+            12 + 88
+        );
+
+        let german = DigitVocabulary::german();
+        assert_eq!(
+            map_calibration_sum(
+                "fünfundzwanzig, dreizehn\n",
+                &german,
+                ParseMode::DigitsAndWords
+            )
+            .unwrap(),
+            53
+        );
+
+        let french = DigitVocabulary::french();
+        assert_eq!(
+            map_calibration_sum("deux-mille-neuf", &french, ParseMode::DigitsAndWords).unwrap(),
+            29
+        );
+
+        let roman = DigitVocabulary::roman();
+        assert_eq!(
+            map_calibration_sum("chapter IX, verse IV", &roman, ParseMode::DigitsAndWords).unwrap(),
+            94
+        );
+        assert_eq!(
+            map_calibration_sum("VIII", &roman, ParseMode::DigitsAndWords).unwrap(),
+            88
+        );
+        // not inside other words, numerals still count anywhere
+        assert_eq!(
+            map_calibration_sum("VIVID 3MIX", &roman, ParseMode::DigitsAndWords).unwrap(),
+            33
+        );
+        let bytes = ByteScanner::new(&roman, ParseMode::DigitsAndWords);
+        assert_eq!(bytes.parse_line(b"chapter IX, verse IV"), Some(94));
+        assert_eq!(bytes.parse_line(b"VIII"), Some(88));
+    }
+
+    #[test]
+    fn zero_on_request() {
+        let input = "zero5one0";
+        assert_eq!(
+            map_calibration_sum(
+                input,
+                &DigitVocabulary::english(),
+                ParseMode::DigitsAndWords
+            )
+            .unwrap(),
+            51
+        );
+        assert_eq!(
+            map_calibration_sum(
+                input,
+                &DigitVocabulary::english().with_zero(),
+                ParseMode::DigitsAndWords
+            )
+            .unwrap(),
+            0
+        );
+        assert_eq!(
+            map_calibration_sum(
+                "IV0",
+                &DigitVocabulary::roman().with_zero(),
+                ParseMode::DigitsAndWords
+            )
+            .unwrap(),
+            40
+        );
+    }
+
+    #[test]
+    fn vocabulary_from_file_format() {
+        let vocabulary: DigitVocabulary = "# Dutch, partly
+een 1
+twee 2
+
+drie    3
+"
+        .parse()
+        .unwrap();
+        assert_eq!(
+            map_calibration_sum("tweeeen7drie", &vocabulary, ParseMode::DigitsAndWords).unwrap(),
+            23
+        );
+        // the numerals of all 66 scripts and 3 words
+        assert_eq!(vocabulary.words().count(), 9 * 66 + 3);
+
+        assert!("een".parse::<DigitVocabulary>().is_err());
+        assert!("tien 10".parse::<DigitVocabulary>().is_err());
     }
 }
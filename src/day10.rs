@@ -1,27 +1,43 @@
-use core::str::FromStr;
-use std::fmt::Display;
-
-fn main() {
-    let input = include_str!("../assets/day10Input.txt");
-    let part_1 = run_part_1(input);
-    println!("part 1: {part_1}");
+#[cfg(feature = "std")]
+use crate::visualize::FrameRecorder;
+use crate::visualize::{Canvas, Render, Rgb};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{convert::Infallible, fmt::Display, str::FromStr};
+#[cfg(feature = "std")]
+use std::io;
+
+pub fn run_part_1(input: &str) -> usize {
+    let mut map: Map = input.parse().unwrap();
 
-    let part_2 = run_part_2(input);
-    println!("part 2: {part_2}");
+    map.traverse_loop() / 2
 }
 
-fn run_part_1(input: &str) -> usize {
+pub fn run_part_2(input: &str) -> usize {
     let mut map: Map = input.parse().unwrap();
 
-    map.traverse_loop() / 2
+    let Ok(_) = map.partition(|_| Ok::<_, Infallible>(()));
+    map.make_all_unclassified_inside();
+    map.count_inside()
 }
 
-fn run_part_2(input: &str) -> usize {
+/// Part 2, recording the flood fill a step at a time and then the final classification
+#[cfg(feature = "std")]
+pub fn record_part_2(input: &str, recorder: &mut FrameRecorder) -> io::Result<usize> {
     let mut map: Map = input.parse().unwrap();
 
-    map.partition();
+    let tweens = map.partition(|frame| recorder.record(frame).map(|_| ()))?;
+    recorder.flush(&Interspersed {
+        map: &map,
+        tweens: &tweens,
+    })?;
     map.make_all_unclassified_inside();
-    map.count_inside()
+    recorder.write(&map)?;
+    Ok(map.count_inside())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -84,30 +100,12 @@ impl Tile {
 
     fn connects(&self, towards: Direction) -> bool {
         match self {
-            Tile::Vertical => match towards {
-                Direction::North | Direction::South => true,
-                _ => false,
-            },
-            Tile::Horizontal => match towards {
-                Direction::East | Direction::West => true,
-                _ => false,
-            },
-            Tile::NorthToEast => match towards {
-                Direction::East | Direction::North => true,
-                _ => false,
-            },
-            Tile::NorthToWest => match towards {
-                Direction::West | Direction::North => true,
-                _ => false,
-            },
-            Tile::SouthToEast => match towards {
-                Direction::East | Direction::South => true,
-                _ => false,
-            },
-            Tile::SouthToWest => match towards {
-                Direction::West | Direction::South => true,
-                _ => false,
-            },
+            Tile::Vertical => matches!(towards, Direction::North | Direction::South),
+            Tile::Horizontal => matches!(towards, Direction::East | Direction::West),
+            Tile::NorthToEast => matches!(towards, Direction::East | Direction::North),
+            Tile::NorthToWest => matches!(towards, Direction::West | Direction::North),
+            Tile::SouthToEast => matches!(towards, Direction::East | Direction::South),
+            Tile::SouthToWest => matches!(towards, Direction::West | Direction::South),
             Tile::Ground => false,
             Tile::Start => true,
         }
@@ -181,7 +179,7 @@ impl FromStr for Tile {
 }
 
 impl Display for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let s = match self {
             Tile::Vertical => "|",
             Tile::Horizontal => "-",
@@ -250,7 +248,7 @@ impl Grid for TweenMap {
 }
 
 impl Display for TweenMap {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut s = String::new();
 
         for row in &self.0 {
@@ -343,13 +341,13 @@ impl Map {
         } else {
             Some(self.0[row - 1][col])
         };
-        let down = self.0.get(row + 1).map(|r| r.get(col)).flatten();
+        let down = self.0.get(row + 1).and_then(|r| r.get(col));
         let left = if col == 0 {
             None
         } else {
             Some(self.0[row][col - 1])
         };
-        let right = self.0.get(row).map(|r| r.get(col + 1)).flatten();
+        let right = self.0.get(row).and_then(|r| r.get(col + 1));
 
         let mut dirs = Vec::with_capacity(2);
         if up.is_some_and(|(t, _)| t.connects(Direction::South)) {
@@ -460,14 +458,15 @@ impl Map {
     fn make_tweens(&self) -> TweenMap {
         let mut map = vec![Vec::with_capacity(self.0[0].len() + 1); self.0.len() + 1];
         // Tweens go around the outside edge of the grid, so len + 1
-        for row in 0..self.0.len() + 1 {
+        for (row, tweens) in map.iter_mut().enumerate() {
             for col in 0..self.0[0].len() + 1 {
-                map[row].push((self.tween_at(row, col), false))
+                tweens.push((self.tween_at(row, col), false))
             }
         }
         TweenMap(map)
     }
 
+    #[cfg(feature = "std")]
     fn display_interspersed(&self, tweens: &TweenMap) {
         let mut s = String::new();
         for i in 0..tweens.0.len() {
@@ -495,10 +494,19 @@ impl Map {
         println!("{s}")
     }
 
-    pub fn partition(&mut self) {
+    /// Flood fills the tweens from the outside edge, any tile the fill surrounds is Outside.
+    /// `on_step` sees the map after every tween visited, returning the filled tweens.
+    pub fn partition<E>(
+        &mut self,
+        mut on_step: impl FnMut(&Interspersed) -> Result<(), E>,
+    ) -> Result<TweenMap, E> {
+        #[cfg_attr(not(feature = "std"), allow(unused_variables))]
         let loop_size = self.traverse_loop();
-        println!("Loop size: {loop_size}");
-        println!("farthest point: {}", loop_size / 2);
+        #[cfg(feature = "std")]
+        {
+            println!("Loop size: {loop_size}");
+            println!("farthest point: {}", loop_size / 2);
+        }
 
         let mut tweens = self.make_tweens();
 
@@ -509,6 +517,10 @@ impl Map {
                 continue;
             }
             tweens.0[row][col].1 = true;
+            on_step(&Interspersed {
+                map: self,
+                tweens: &tweens,
+            })?;
 
             stack.append(&mut tweens.adjacent_cells(row, col));
         }
@@ -526,14 +538,15 @@ impl Map {
             }
         }
 
+        #[cfg(feature = "std")]
         self.display_interspersed(&tweens);
+        Ok(tweens)
     }
 
     pub fn make_all_unclassified_inside(&mut self) {
         self.0
             .iter_mut()
-            .map(|r| r.iter_mut())
-            .flatten()
+            .flat_map(|r| r.iter_mut())
             .map(|(_t, c)| c.get_or_insert(Classification::Inside))
             .count();
     }
@@ -541,8 +554,7 @@ impl Map {
     pub fn count_inside(&self) -> usize {
         self.0
             .iter()
-            .map(|r| r.iter())
-            .flatten()
+            .flat_map(|r| r.iter())
             .filter(|(_t, c)| c.is_some_and(|c| c == Classification::Inside))
             .count()
     }
@@ -564,6 +576,63 @@ impl Grid for Map {
     }
 }
 
+impl Map {
+    fn tile_color(tile: Tile, class: Option<Classification>) -> Rgb {
+        match class {
+            Some(Classification::Loop) => Rgb::ORANGE,
+            Some(Classification::Outside) => Rgb::BLUE,
+            Some(Classification::Inside) => Rgb::GREEN,
+            None if tile == Tile::Ground => Rgb::DARK_GREY,
+            None => Rgb::GREY,
+        }
+    }
+
+    fn draw_tiles(&self, canvas: &mut Canvas) {
+        for (row, tiles) in self.0.iter().enumerate() {
+            for (col, (tile, class)) in tiles.iter().enumerate() {
+                canvas.set(row * 2 + 1, col * 2 + 1, Map::tile_color(*tile, *class));
+            }
+        }
+    }
+}
+
+// Uses the same layout as `Interspersed` so it can follow on from the flood fill frames
+impl Render for Map {
+    fn render(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.width() * 2 + 1, self.height() * 2 + 1, Rgb::BLACK);
+        self.draw_tiles(&mut canvas);
+        canvas
+    }
+}
+
+/// The map drawn with its tweens in between the tiles, the same layout as `Map::display_interspersed`
+struct Interspersed<'a> {
+    map: &'a Map,
+    tweens: &'a TweenMap,
+}
+
+impl Render for Interspersed<'_> {
+    fn render(&self) -> Canvas {
+        // tweens land on even rows/columns, tiles on odd
+        let mut canvas = Canvas::new(
+            self.tweens.width() * 2 - 1,
+            self.tweens.height() * 2 - 1,
+            Rgb::BLACK,
+        );
+
+        for (row, tweens) in self.tweens.0.iter().enumerate() {
+            for (col, (_, visited)) in tweens.iter().enumerate() {
+                if *visited {
+                    canvas.set(row * 2, col * 2, Rgb::CYAN);
+                }
+            }
+        }
+        self.map.draw_tiles(&mut canvas);
+
+        canvas
+    }
+}
+
 impl FromStr for Map {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -584,7 +653,7 @@ impl FromStr for Map {
 }
 
 impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut s = String::new();
 
         for row in &self.0 {
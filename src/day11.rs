@@ -1,22 +1,25 @@
+#[cfg(feature = "std")]
+use crate::visualize::FrameRecorder;
+use crate::{
+    num::Num,
+    visualize::{Canvas, Render, Rgb},
+};
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::{
     fmt::{Display, Write},
     str::FromStr,
 };
-use std::collections::HashSet;
-
-fn main() {
-    let input = include_str!("../assets/day11Input.txt");
-    let res_1 = run_part_1(input);
-    println!("part 1: {res_1}");
-    let res_2 = run_part_2(input);
-    println!("part 2: {res_2}");
-    // 293152727264 -> incorrect (forgot to change column expansion too)
-    // 611998089572 -> correct
-}
+#[cfg(feature = "std")]
+use std::io;
 
-fn run_part_1(input: &str) -> usize {
-    let mut star_chart: StarChart = input.parse().unwrap();
-    star_chart.expand(2);
+pub fn run_part_1<N: Num>(input: &str) -> N {
+    let mut star_chart: StarChart<N> = input.parse().unwrap();
+    star_chart.expand(N::from_usize(2));
 
     star_chart
         .pairs()
@@ -24,9 +27,9 @@ fn run_part_1(input: &str) -> usize {
         .sum()
 }
 
-fn run_part_2(input: &str) -> usize {
-    let mut star_chart: StarChart = input.parse().unwrap();
-    star_chart.expand(1000000);
+pub fn run_part_2<N: Num>(input: &str) -> N {
+    let mut star_chart: StarChart<N> = input.parse().unwrap();
+    star_chart.expand(N::from_usize(1000000));
 
     star_chart
         .pairs()
@@ -34,59 +37,95 @@ fn run_part_2(input: &str) -> usize {
         .sum()
 }
 
+/// Records the chart before expansion, with only its rows expanded, then fully expanded (as in part 1)
+#[cfg(feature = "std")]
+pub fn record_expansion(input: &str, recorder: &mut FrameRecorder) -> io::Result<()> {
+    let mut star_chart: StarChart<usize> = input.parse().unwrap();
+    recorder.write(&star_chart)?;
+    star_chart.expand_rows(2);
+    recorder.write(&star_chart)?;
+    star_chart.expand_cols(2);
+    recorder.write(&star_chart)?;
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy)]
-struct Galaxy {
-    row: usize,
-    col: usize,
+struct Galaxy<N> {
+    row: N,
+    col: N,
 }
 
-impl Galaxy {
-    fn taxi_cab_distance(&self, other: &Self) -> usize {
+impl<N: Num> Galaxy<N> {
+    fn taxi_cab_distance(&self, other: &Self) -> N {
         self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
     }
 }
 
 #[derive(Debug, Clone)]
-struct StarChart {
-    width: usize,
-    height: usize,
-    galaxies: Vec<Galaxy>,
+struct StarChart<N> {
+    width: N,
+    height: N,
+    galaxies: Vec<Galaxy<N>>,
 }
 
-impl StarChart {
-    fn expand(&mut self, times: usize) {
-        let height_set: HashSet<usize> = (0..self.height).collect();
-        let occupied_rows: HashSet<usize> = self.galaxies.iter().map(|g| g.row).collect();
-        let mut expand_rows: Vec<_> = height_set.difference(&occupied_rows).collect();
-        expand_rows.sort();
+impl<N: Num> StarChart<N> {
+    fn expand(&mut self, times: N) {
+        self.expand_rows(times);
+        self.expand_cols(times);
+    }
 
-        let width_set: HashSet<usize> = (0..self.width).collect();
-        let occupied_cols: HashSet<usize> = self.galaxies.iter().map(|g| g.col).collect();
-        let mut expand_cols: Vec<_> = width_set.difference(&occupied_cols).collect();
-        expand_cols.sort();
+    fn expand_rows(&mut self, times: N) {
+        let expand_rows = self.empty_rows();
+        let height = self.height.to_usize();
 
-        let mut rows_expanded_at = vec![0; self.height];
+        let mut rows_expanded_at = vec![N::ZERO; height];
         for row in &expand_rows {
-            let new = vec![rows_expanded_at[**row] + times - 1; self.height - *row];
-            let _: Vec<_> = rows_expanded_at.splice(*row..&self.height, new).collect();
+            let new = vec![rows_expanded_at[*row] + times - N::ONE; height - *row];
+            let _: Vec<_> = rows_expanded_at.splice(*row..height, new).collect();
         }
 
-        let mut cols_expanded_at = vec![0; self.width];
+        self.height += N::from_usize(expand_rows.len()) * (times - N::ONE);
+
+        for galaxy in &mut self.galaxies {
+            galaxy.row += rows_expanded_at[galaxy.row.to_usize()];
+        }
+    }
+
+    fn expand_cols(&mut self, times: N) {
+        let expand_cols = self.empty_cols();
+        let width = self.width.to_usize();
+
+        let mut cols_expanded_at = vec![N::ZERO; width];
         for col in &expand_cols {
-            let new = vec![cols_expanded_at[**col] + times - 1; self.width - *col];
-            let _: Vec<_> = cols_expanded_at.splice(*col..&self.width, new).collect();
+            let new = vec![cols_expanded_at[*col] + times - N::ONE; width - *col];
+            let _: Vec<_> = cols_expanded_at.splice(*col..width, new).collect();
         }
 
-        self.height += expand_rows.len();
-        self.width += expand_cols.len();
+        self.width += N::from_usize(expand_cols.len()) * (times - N::ONE);
 
         for galaxy in &mut self.galaxies {
-            galaxy.row += rows_expanded_at[galaxy.row];
-            galaxy.col += cols_expanded_at[galaxy.col];
+            galaxy.col += cols_expanded_at[galaxy.col.to_usize()];
         }
     }
 
-    fn pairs(&self) -> GalaxyPairs {
+    fn empty_rows(&self) -> Vec<usize> {
+        let height_set: BTreeSet<usize> = (0..self.height.to_usize()).collect();
+        let occupied_rows: BTreeSet<usize> =
+            self.galaxies.iter().map(|g| g.row.to_usize()).collect();
+        // already in order, coming from sets
+        height_set.difference(&occupied_rows).copied().collect()
+    }
+
+    fn empty_cols(&self) -> Vec<usize> {
+        let width_set: BTreeSet<usize> = (0..self.width.to_usize()).collect();
+        let occupied_cols: BTreeSet<usize> =
+            self.galaxies.iter().map(|g| g.col.to_usize()).collect();
+        // already in order, coming from sets
+        width_set.difference(&occupied_cols).copied().collect()
+    }
+
+    fn pairs(&self) -> GalaxyPairs<'_, N> {
+        #[cfg(feature = "std")]
         println!("{} galaxies", self.galaxies.len());
         GalaxyPairs {
             galaxies: &self.galaxies,
@@ -97,7 +136,7 @@ impl StarChart {
     }
 }
 
-impl FromStr for StarChart {
+impl<N: Num> FromStr for StarChart<N> {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -110,30 +149,57 @@ impl FromStr for StarChart {
             galaxies.append(
                 &mut line
                     .match_indices('#')
-                    .map(|(i, _)| Galaxy { row, col: i })
+                    .map(|(i, _)| Galaxy {
+                        row: N::from_usize(row),
+                        col: N::from_usize(i),
+                    })
                     .collect(),
             )
         }
         let width = width.ok_or_else(|| "Expected at least 1 line".to_string())?;
         Ok(StarChart {
-            width,
-            height,
+            width: N::from_usize(width),
+            height: N::from_usize(height),
             galaxies,
         })
     }
 }
 
-impl Display for StarChart {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut chars = vec![vec![".".to_string(); self.width]; self.height];
+// Empty rows and columns are shaded so the expansion can be seen between frames
+impl<N: Num> Render for StarChart<N> {
+    fn render(&self) -> Canvas {
+        let (width, height) = (self.width.to_usize(), self.height.to_usize());
+        let mut canvas = Canvas::new(width, height, Rgb::BLACK);
+
+        for row in self.empty_rows() {
+            for col in 0..width {
+                canvas.set(row, col, Rgb::DARK_GREY);
+            }
+        }
+        for col in self.empty_cols() {
+            for row in 0..height {
+                canvas.set(row, col, Rgb::DARK_GREY);
+            }
+        }
+        for galaxy in &self.galaxies {
+            canvas.set(galaxy.row.to_usize(), galaxy.col.to_usize(), Rgb::WHITE);
+        }
+
+        canvas
+    }
+}
+
+impl<N: Num> Display for StarChart<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut chars = vec![vec![".".to_string(); self.width.to_usize()]; self.height.to_usize()];
 
         for (i, galaxy) in self.galaxies.iter().enumerate() {
-            chars[galaxy.row][galaxy.col] = i.to_string();
+            chars[galaxy.row.to_usize()][galaxy.col.to_usize()] = i.to_string();
         }
 
         for line in chars {
             for c in line {
-                let _ = f.write_str(&c)?;
+                f.write_str(&c)?;
             }
             let _ = f.write_char('\n');
         }
@@ -141,14 +207,14 @@ impl Display for StarChart {
     }
 }
 
-struct GalaxyPairs<'a> {
-    galaxies: &'a Vec<Galaxy>,
+struct GalaxyPairs<'a, N> {
+    galaxies: &'a Vec<Galaxy<N>>,
     curr_left: usize,
     curr_right: usize,
 }
 
-impl<'a> Iterator for GalaxyPairs<'a> {
-    type Item = (&'a Galaxy, &'a Galaxy);
+impl<'a, N> Iterator for GalaxyPairs<'a, N> {
+    type Item = (&'a Galaxy<N>, &'a Galaxy<N>);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.curr_right + 1 < self.galaxies.len() {
@@ -188,6 +254,46 @@ mod test {
 #...#.....
 "##;
 
-        assert_eq!(run_part_1(input), 374)
+        assert_eq!(run_part_1::<usize>(input), 374)
+    }
+
+    #[test]
+    fn render_expanded() {
+        let input = r##"...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+"##;
+
+        let mut star_chart: StarChart<usize> = input.parse().unwrap();
+        star_chart.expand(2);
+        let canvas = star_chart.render();
+
+        // 2 empty rows and 3 empty columns, each doubled
+        assert_eq!(canvas.height(), 12);
+        assert_eq!(canvas.width(), 13);
+        assert_eq!(canvas.get(0, 4), Some(Rgb::WHITE));
+        assert_eq!(canvas.get(0, 2), Some(Rgb::DARK_GREY));
+        assert_eq!(canvas.get(0, 0), Some(Rgb::BLACK));
+    }
+
+    #[test]
+    fn wide_expansion() {
+        let input = r##"#..
+...
+..#
+"##;
+
+        let mut star_chart: StarChart<u128> = input.parse().unwrap();
+        star_chart.expand(10u128.pow(20));
+        let (l, r) = star_chart.pairs().next().unwrap();
+
+        assert_eq!(l.taxi_cab_distance(r), 2 * 10u128.pow(20) + 2);
     }
 }
@@ -1,17 +1,25 @@
-use core::str::FromStr;
+use crate::{num::Num, rng::XorShift};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+    str::FromStr,
+};
 
-fn main() {
-    let input = include_str!("../assets/day2Input.txt");
-
-    let limit = GameLimits::new(12, 13, 14);
-    let illegal_games = run_part_1(input, limit);
-    println!("illegal_games: {illegal_games}");
-    let sum_of_games_failed = run_part_2(input);
-    println!("games failed: {sum_of_games_failed}");
+pub fn run_part_1<N: Num>(input: &str, limit: GameLimits<N>) -> u32 {
+    run_part_1_with(input, limit, Validation::Lenient)
 }
 
-fn run_part_1(input: &str, limit: GameLimits) -> u32 {
-    let games: Vec<Game> = input.lines().map(|line| line.parse().unwrap()).collect();
+/// `run_part_1` reading the games as `validation` does, so `Warn` can solve
+/// records with empty reveals
+pub fn run_part_1_with<N: Num>(input: &str, limit: GameLimits<N>, validation: Validation) -> u32 {
+    let games = parse_games::<N>(input, validation);
 
     games
         .iter()
@@ -25,131 +33,232 @@ fn run_part_1(input: &str, limit: GameLimits) -> u32 {
         .sum()
 }
 
-fn run_part_2(input: &str) -> u32 {
-    let games: Vec<Game> = input.lines().map(|line| line.parse().unwrap()).collect();
+pub fn run_part_2<N: Num>(input: &str) -> N {
+    run_part_2_with(input, Validation::Lenient)
+}
+
+/// `run_part_2` reading the games as `validation` does
+pub fn run_part_2_with<N: Num>(input: &str, validation: Validation) -> N {
+    let games = parse_games::<N>(input, validation);
+    let colors = colors(&games);
 
-    games.iter().map(|g| g.power()).sum()
+    games.iter().map(|g| g.power(&colors)).sum()
 }
 
-#[derive(Debug, Clone, Copy)]
-struct GameLimits {
-    num_of_colors: Hand,
+fn parse_games<N: Num>(input: &str, validation: Validation) -> Vec<Game<N>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Game::parse(line, i + 1, validation, &mut Vec::new()).unwrap())
+        .collect()
+}
+
+/// Every color appearing in any of the games
+fn colors<N>(games: &[Game<N>]) -> BTreeSet<&str> {
+    games
+        .iter()
+        .flat_map(|g| &g.hands)
+        .flat_map(|h| h.counts.keys())
+        .map(String::as_str)
+        .collect()
 }
 
-impl GameLimits {
-    fn new(red: u32, green: u32, blue: u32) -> Self {
+/// The most cubes of each color a bag holds, colors not given aren't in the bag at all
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameLimits<N> {
+    num_of_colors: Hand<N>,
+}
+
+impl<N: Num> GameLimits<N> {
+    pub fn new(red: N, green: N, blue: N) -> Self {
         GameLimits {
             num_of_colors: Hand::new(red, green, blue),
         }
     }
+
+    /// A bag without any cubes
+    pub fn empty() -> Self {
+        GameLimits {
+            num_of_colors: Hand::default(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-struct Hand {
-    num_red: u32,
-    num_green: u32,
-    num_blue: u32,
+impl<N: Num> GameLimits<N> {
+    pub fn get(&self, color: &str) -> N {
+        self.num_of_colors.count(color)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, N)> {
+        self.num_of_colors
+            .counts
+            .iter()
+            .map(|(color, num)| (color.as_str(), *num))
+    }
+
+    pub fn total(&self) -> N {
+        self.num_of_colors.counts.values().copied().sum()
+    }
 }
 
-impl Hand {
-    fn new(red: u32, green: u32, blue: u32) -> Self {
-        Hand {
-            num_red: red,
-            num_green: green,
-            num_blue: blue,
+impl<S: Into<String>, N: Num> FromIterator<(S, N)> for GameLimits<N> {
+    fn from_iter<T: IntoIterator<Item = (S, N)>>(iter: T) -> Self {
+        GameLimits {
+            num_of_colors: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// The number of cubes of each color, any color not mentioned is 0
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Hand<N> {
+    counts: BTreeMap<String, N>,
+}
+
+impl<N: Num> Hand<N> {
+    fn new(red: N, green: N, blue: N) -> Self {
+        [("red", red), ("green", green), ("blue", blue)]
+            .into_iter()
+            .collect()
+    }
+
+    fn count(&self, color: &str) -> N {
+        self.counts.get(color).copied().unwrap_or_default()
+    }
+
+    fn add(&mut self, color: &str, num: N) {
+        match self.counts.get_mut(color) {
+            Some(count) => *count += num,
+            None => {
+                self.counts.insert(color.to_string(), num);
+            }
         }
     }
 
     fn is_greater(&self, other: &Self) -> bool {
-        self.num_red > other.num_red
-            || self.num_green > other.num_green
-            || self.num_blue > other.num_blue
+        self.counts
+            .iter()
+            .any(|(color, num)| *num > other.count(color))
     }
 }
 
-impl FromStr for Hand {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let colors = s.split(',');
+impl<S: Into<String>, N: Num> FromIterator<(S, N)> for Hand<N> {
+    fn from_iter<T: IntoIterator<Item = (S, N)>>(iter: T) -> Self {
+        let mut hand = Hand::default();
+        for (color, num) in iter {
+            hand.add(&color.into(), num);
+        }
+        hand
+    }
+}
 
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
+impl<N: Num> Hand<N> {
+    /// Anything suspicious is added to `issues`. An empty reveal is an error when `Lenient`,
+    /// as it always was, otherwise an empty hand.
+    fn parse(
+        s: &str,
+        line: usize,
+        reveal: usize,
+        validation: Validation,
+        issues: &mut Vec<Issue>,
+    ) -> Result<Self, String> {
+        let mut hand = Hand::default();
+        if validation != Validation::Lenient && s.trim().is_empty() {
+            issues.push(Issue::EmptyReveal { line, reveal });
+            return Ok(hand);
+        }
 
-        for color_str in colors {
+        for color_str in s.split(',') {
             let split: Vec<_> = color_str.split_ascii_whitespace().collect();
             if split.len() != 2 {
                 return Err(format!("Expected 2 elements in a color amount: {split:?}"));
             } else {
-                let num = u32::from_str_radix(split[0], 10)
+                let num = split[0]
+                    .parse::<N>()
                     .map_err(|e| format!("number of each color must be a decimal number: {e}"))?;
+                let color = split[1].to_string();
 
-                match split[1] {
-                    "red" => red += num,
-                    "green" => green += num,
-                    "blue" => blue += num,
-                    other => return Err(format!("Unexpected color '{other}'")),
+                if num == N::ZERO {
+                    issues.push(Issue::ZeroCount {
+                        line,
+                        reveal,
+                        color: color.clone(),
+                    });
                 }
+                if hand.counts.contains_key(&color) {
+                    issues.push(Issue::DuplicateColor {
+                        line,
+                        reveal,
+                        color: color.clone(),
+                    });
+                }
+                hand.add(&color, num);
             }
         }
 
-        Ok(Hand::new(red, green, blue))
+        Ok(hand)
+    }
+}
+
+impl<N: Num> FromStr for Hand<N> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Hand::parse(s, 1, 1, Validation::Lenient, &mut Vec::new())
     }
 }
 
 #[derive(Debug, Clone)]
-struct Game {
+struct Game<N> {
     id: u32,
-    hands: Vec<Hand>,
+    hands: Vec<Hand<N>>,
 }
 
-impl Game {
-    fn is_illegal(&self, limits: &GameLimits) -> bool {
+impl<N: Num> Game<N> {
+    fn is_illegal(&self, limits: &GameLimits<N>) -> bool {
         for hand in &self.hands {
             if hand.is_greater(&limits.num_of_colors) {
                 return true;
             }
         }
-        return false;
+        false
     }
 
-    fn min_game_size(&self) -> Hand {
-        let mut max_red = 0;
-        let mut max_green = 0;
-        let mut max_blue = 0;
+    fn min_game_size(&self) -> Hand<N> {
+        let mut min_size: Hand<N> = Hand::default();
 
-        for hand in &self.hands {
-            if hand.num_red > max_red {
-                max_red = hand.num_red;
-            }
-            if hand.num_green > max_green {
-                max_green = hand.num_green;
-            }
-            if hand.num_blue > max_blue {
-                max_blue = hand.num_blue;
+        for (color, num) in self.hands.iter().flat_map(|h| &h.counts) {
+            if *num > min_size.count(color) {
+                min_size.counts.insert(color.clone(), *num);
             }
         }
 
-        Hand {
-            num_red: max_red,
-            num_green: max_green,
-            num_blue: max_blue,
-        }
+        min_size
     }
 
-    fn power(&self) -> u32 {
-        let Hand {
-            num_red,
-            num_green,
-            num_blue,
-        } = self.min_game_size();
-        num_red * num_green * num_blue
+    /// The product of the least cubes possible of each of `colors`, 0 if any never appears
+    fn power(&self, colors: &BTreeSet<&str>) -> N {
+        let min_size = self.min_game_size();
+        colors.iter().map(|color| min_size.count(color)).product()
     }
 }
 
-impl FromStr for Game {
+impl<N: Num> FromStr for Game<N> {
     type Err = String;
     fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Game::parse(line, 1, Validation::Lenient, &mut Vec::new())
+    }
+}
+
+impl<N: Num> Game<N> {
+    /// Parses the record on `line_number`, adding anything suspicious to `issues`.
+    /// Solving and `validate` both go through here, so with the same `validation`
+    /// they agree on what parses.
+    fn parse(
+        line: &str,
+        line_number: usize,
+        validation: Validation,
+        issues: &mut Vec<Issue>,
+    ) -> Result<Self, String> {
         let colon_split: Vec<_> = line.split(':').collect();
 
         if colon_split.len() != 2 {
@@ -169,16 +278,16 @@ impl FromStr for Game {
         if id_str.is_none() {
             return Err("Lines must have an id 'Game <id>'".to_string());
         }
-        let id = u32::from_str_radix(id_str.unwrap(), 10)
+        let id = id_str
+            .unwrap()
+            .parse::<u32>()
             .map_err(|e| format!("game id must be a decimal number: {e}"))?;
 
-        let hands_res: Vec<_> = hands_str.split(";").map(Hand::from_str).collect();
-        let mut hands = Vec::with_capacity(hands_res.len());
-        for res in hands_res {
-            if let Ok(hand) = res {
-                hands.push(hand);
-            } else {
-                return Err(format!("failed parsing hand: {res:?}"));
+        let mut hands = Vec::new();
+        for (i, hand) in hands_str.split(";").enumerate() {
+            match Hand::parse(hand, line_number, i + 1, validation, issues) {
+                Ok(hand) => hands.push(hand),
+                Err(e) => return Err(format!("failed parsing hand {}: {e}", i + 1)),
             }
         }
 
@@ -186,6 +295,1120 @@ impl FromStr for Game {
     }
 }
 
+impl<N: Num> Display for Game<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+        for (i, hand) in self.hands.iter().enumerate() {
+            let sep = if i == 0 { " " } else { "; " };
+            f.write_str(sep)?;
+            for (j, (color, num)) in hand.counts.iter().enumerate() {
+                let sep = if j == 0 { "" } else { ", " };
+                write!(f, "{sep}{num} {color}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Each game's id and minimum bag, with counts in the order of `colors`
+fn min_sizes<N: Num>(games: &[Game<N>], colors: &[&str]) -> Vec<(u32, Vec<N>)> {
+    games
+        .iter()
+        .map(|g| {
+            let min_size = g.min_game_size();
+            (g.id, colors.iter().map(|c| min_size.count(c)).collect())
+        })
+        .collect()
+}
+
+fn legal_games<N: Num>(min_sizes: &[(u32, Vec<N>)], limit: &[N]) -> usize {
+    min_sizes
+        .iter()
+        .filter(|(_, min)| min.iter().zip(limit).all(|(m, l)| m <= l))
+        .count()
+}
+
+/// The least limits, over every color in the input, under which exactly `legal` games are possible.
+/// None of them is below another in every color, but each is as low as it can go.
+pub fn limit_frontier<N: Num>(input: &str, legal: usize) -> Vec<GameLimits<N>> {
+    let games: Vec<Game<N>> = input.lines().map(|line| line.parse().unwrap()).collect();
+    let colors: Vec<_> = colors(&games).into_iter().collect();
+    let min_sizes = min_sizes(&games, &colors);
+
+    // A least limit only ever sits on some game's minimum (or 0) in each color
+    let values: Vec<Vec<N>> = (0..colors.len())
+        .map(|i| {
+            let mut values: BTreeSet<N> = min_sizes.iter().map(|(_, min)| min[i]).collect();
+            values.insert(N::ZERO);
+            values.into_iter().collect()
+        })
+        .collect();
+
+    let mut exact: Vec<Vec<N>> = Vec::new();
+    let mut odometer = vec![0; colors.len()];
+    loop {
+        let limit: Vec<_> = odometer.iter().zip(&values).map(|(i, v)| v[*i]).collect();
+        if legal_games(&min_sizes, &limit) == legal {
+            exact.push(limit);
+        }
+
+        let Some(wheel) = (0..colors.len()).find(|w| odometer[*w] + 1 < values[*w].len()) else {
+            break;
+        };
+        odometer[wheel] += 1;
+        odometer[..wheel].fill(0);
+    }
+
+    // in order of the first color's limit, then the second's...
+    exact.sort();
+    let below = |a: &Vec<N>, b: &Vec<N>| a != b && a.iter().zip(b).all(|(a, b)| a <= b);
+    exact
+        .iter()
+        .filter(|limit| !exact.iter().any(|other| below(other, limit)))
+        .map(|limit| colors.iter().copied().zip(limit.iter().copied()).collect())
+        .collect()
+}
+
+/// The limits with the fewest cubes under which every one of `ids` is possible, with that
+/// number of cubes. `None` if any of the ids isn't in the input.
+pub fn smallest_limits<N: Num>(input: &str, ids: &[u32]) -> Option<(GameLimits<N>, N)> {
+    let games: Vec<Game<N>> = input.lines().map(|line| line.parse().unwrap()).collect();
+
+    let mut limits = GameLimits::empty();
+    for id in ids {
+        let min_size = games.iter().find(|g| g.id == *id)?.min_game_size();
+        for (color, num) in min_size.counts {
+            if num > limits.get(&color) {
+                limits.num_of_colors.counts.insert(color, num);
+            }
+        }
+    }
+    let total = limits.total();
+    Some((limits, total))
+}
+
+/// How many games are possible with each of `limits` cubes of every color in the input
+pub fn sweep<N: Num>(input: &str, limits: impl IntoIterator<Item = N>) -> Vec<(N, usize)> {
+    let games: Vec<Game<N>> = input.lines().map(|line| line.parse().unwrap()).collect();
+    let colors: Vec<_> = colors(&games).into_iter().collect();
+    let min_sizes = min_sizes(&games, &colors);
+
+    limits
+        .into_iter()
+        .map(|limit| (limit, legal_games(&min_sizes, &vec![limit; colors.len()])))
+        .collect()
+}
+
+/// How closely game records are checked before solving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Validation {
+    /// Only what's needed to parse, a color given twice in a reveal is summed
+    #[default]
+    Lenient,
+    /// Report every `Issue` but carry on
+    Warn,
+    /// Any `Issue` is an error
+    Strict,
+}
+
+/// Something suspicious in the game records which lenient parsing lets through.
+/// Reveals are counted from 1 within their game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    DuplicateColor {
+        line: usize,
+        reveal: usize,
+        color: String,
+    },
+    ZeroCount {
+        line: usize,
+        reveal: usize,
+        color: String,
+    },
+    EmptyReveal {
+        line: usize,
+        reveal: usize,
+    },
+    DuplicateId {
+        line: usize,
+        id: u32,
+    },
+    OutOfSequence {
+        line: usize,
+        id: u32,
+        /// `None` after game `u32::MAX`
+        expected: Option<u32>,
+    },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::DuplicateColor {
+                line,
+                reveal,
+                color,
+            } => write!(f, "line {line}: '{color}' given twice in reveal {reveal}"),
+            Issue::ZeroCount {
+                line,
+                reveal,
+                color,
+            } => write!(f, "line {line}: 0 '{color}' in reveal {reveal}"),
+            Issue::EmptyReveal { line, reveal } => {
+                write!(f, "line {line}: reveal {reveal} is empty")
+            }
+            Issue::DuplicateId { line, id } => write!(f, "line {line}: game {id} seen before"),
+            Issue::OutOfSequence {
+                line,
+                id,
+                expected: Some(expected),
+            } => write!(f, "line {line}: game {id}, expected game {expected}"),
+            Issue::OutOfSequence {
+                line,
+                id,
+                expected: None,
+            } => write!(f, "line {line}: game {id}, expected no more games"),
+        }
+    }
+}
+
+/// Checks every game record with the same parser the solvers use, failing on anything
+/// which wouldn't parse at all. `Lenient` doesn't look any further, `Warn` returns every
+/// issue found and `Strict` fails listing them all.
+pub fn validate<N: Num>(input: &str, validation: Validation) -> Result<Vec<Issue>, String> {
+    let mut issues = Vec::new();
+    let mut ids = BTreeSet::new();
+    // `None` once game u32::MAX has been seen, nothing can follow it
+    let mut expected = Some(1);
+    for (i, record) in input.lines().enumerate() {
+        let line = i + 1;
+        let mut hand_issues = Vec::new();
+        let game = Game::<N>::parse(record, line, validation, &mut hand_issues)
+            .map_err(|e| format!("line {line}: {e}"))?;
+        let id = game.id;
+
+        if !ids.insert(id) {
+            issues.push(Issue::DuplicateId { line, id });
+        } else if Some(id) != expected {
+            issues.push(Issue::OutOfSequence { line, id, expected });
+        }
+        expected = id.checked_add(1);
+        issues.append(&mut hand_issues);
+    }
+
+    match validation {
+        Validation::Lenient => Ok(Vec::new()),
+        Validation::Strict if !issues.is_empty() => {
+            let issues: Vec<_> = issues.iter().map(ToString::to_string).collect();
+            Err(issues.join("\n"))
+        }
+        _ => Ok(issues),
+    }
+}
+
+/// A question about the games, in the form
+///
+/// `<sum|max|min|count> <field> [where <condition> [and <condition>]...] [by color]`
+///
+/// where a field is `id`, `power`, `hands` (how many), `cubes` (shown over every hand)
+/// or a color (the least of it the bag could hold), and a condition is either
+/// `<field> <op> <number>` or `<any hand|all hands> <color> <op> <number>`, with
+/// `op` one of `<`, `<=`, `=`, `!=`, `>=` and `>`. `count` needs no field.
+///
+/// With `by color` the question is asked once for each color in the input,
+/// using `color` for whichever color it's asked about, as in `max color by color`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query<N> {
+    aggregate: Aggregate,
+    field: Option<Field>,
+    conditions: Vec<Condition<N>>,
+    by_color: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Sum,
+    Max,
+    Min,
+    Count,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ColorRef {
+    Named(String),
+    // `color`, meaning each color in turn when grouping
+    Grouped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Id,
+    Power,
+    Hands,
+    Cubes,
+    Color(ColorRef),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantifier {
+    Any,
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition<N> {
+    Field(Field, Cmp, N),
+    Hand(Quantifier, ColorRef, Cmp, N),
+}
+
+impl Cmp {
+    fn test<N: Ord>(self, l: N, r: N) -> bool {
+        match self {
+            Cmp::Lt => l < r,
+            Cmp::Le => l <= r,
+            Cmp::Eq => l == r,
+            Cmp::Ne => l != r,
+            Cmp::Ge => l >= r,
+            Cmp::Gt => l > r,
+        }
+    }
+}
+
+impl FromStr for Cmp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "<" => Ok(Cmp::Lt),
+            "<=" => Ok(Cmp::Le),
+            "=" | "==" => Ok(Cmp::Eq),
+            "!=" => Ok(Cmp::Ne),
+            ">=" => Ok(Cmp::Ge),
+            ">" => Ok(Cmp::Gt),
+            other => Err(format!("Unknown comparison '{other}'")),
+        }
+    }
+}
+
+fn parse_color(word: &str) -> ColorRef {
+    match word {
+        "color" => ColorRef::Grouped,
+        color => ColorRef::Named(color.to_string()),
+    }
+}
+
+fn parse_field(word: &str) -> Field {
+    match word {
+        "id" => Field::Id,
+        "power" => Field::Power,
+        "hands" => Field::Hands,
+        "cubes" => Field::Cubes,
+        color => Field::Color(parse_color(color)),
+    }
+}
+
+impl<N> Query<N> {
+    // Every color the query names, rather than leaving to `by color`
+    fn named_colors(&self) -> impl Iterator<Item = &str> {
+        let field = match &self.field {
+            Some(Field::Color(color)) => Some(color),
+            _ => None,
+        };
+        let conditions = self.conditions.iter().filter_map(|c| match c {
+            Condition::Field(Field::Color(color), ..) | Condition::Hand(_, color, ..) => {
+                Some(color)
+            }
+            Condition::Field(..) => None,
+        });
+        field
+            .into_iter()
+            .chain(conditions)
+            .filter_map(|color| match color {
+                ColorRef::Named(name) => Some(name.as_str()),
+                ColorRef::Grouped => None,
+            })
+    }
+}
+
+impl<N: Num> FromStr for Query<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |word: &str| {
+            word.parse::<N>()
+                .map_err(|e| format!("Expected a number, not '{word}': {e}"))
+        };
+
+        let mut words = s.split_ascii_whitespace().peekable();
+        let aggregate = match words.next() {
+            Some("sum") => Aggregate::Sum,
+            Some("max") => Aggregate::Max,
+            Some("min") => Aggregate::Min,
+            Some("count") => Aggregate::Count,
+            Some(other) => return Err(format!("Unknown aggregate '{other}'")),
+            None => return Err("Expected sum, max, min or count".to_string()),
+        };
+
+        let field = match words.peek() {
+            None | Some(&("where" | "by")) if aggregate == Aggregate::Count => None,
+            None | Some(&("where" | "by")) => {
+                return Err("Expected a field after the aggregate".to_string())
+            }
+            Some(word) => Some(parse_field(word)),
+        };
+        if field.is_some() {
+            words.next();
+        }
+
+        let mut conditions = Vec::new();
+        let mut by_color = false;
+        let mut joiner = "where";
+        while let Some(word) = words.next() {
+            if word == "by" {
+                match words.next() {
+                    Some("color") => by_color = true,
+                    _ => return Err("Expected 'by color'".to_string()),
+                }
+                break;
+            }
+            if word != joiner {
+                return Err(format!("Expected '{joiner}' or 'by color', not '{word}'"));
+            }
+            joiner = "and";
+
+            let mut next = |expected: &str| {
+                words
+                    .next()
+                    .ok_or_else(|| format!("Expected {expected} at the end of the query"))
+            };
+            let condition = match next("a condition")? {
+                quantifier @ ("any" | "all") => {
+                    let quantifier = if quantifier == "any" {
+                        Quantifier::Any
+                    } else {
+                        Quantifier::All
+                    };
+                    match next("'hand' or 'hands'")? {
+                        "hand" | "hands" => {}
+                        other => return Err(format!("Expected 'hand' or 'hands', not '{other}'")),
+                    }
+                    let color = parse_color(next("a color")?);
+                    let cmp = next("a comparison")?.parse()?;
+                    Condition::Hand(quantifier, color, cmp, number(next("a number")?)?)
+                }
+                word => {
+                    let cmp = next("a comparison")?.parse()?;
+                    Condition::Field(parse_field(word), cmp, number(next("a number")?)?)
+                }
+            };
+            conditions.push(condition);
+        }
+        if let Some(word) = words.next() {
+            return Err(format!("Unexpected '{word}' after 'by color'"));
+        }
+
+        let query = Query {
+            aggregate,
+            field,
+            conditions,
+            by_color,
+        };
+        if !query.by_color && query.mentions_grouped_color() {
+            return Err("'color' can only be used with 'by color'".to_string());
+        }
+        Ok(query)
+    }
+}
+
+/// The result of a `Query`, `None` for the max or min of no games
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer<N> {
+    Single(Option<N>),
+    ByColor(Vec<(String, Option<N>)>),
+}
+
+impl<N: Display> Display for Answer<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |f: &mut fmt::Formatter<'_>, value: &Option<N>| match value {
+            Some(value) => write!(f, "{value}"),
+            None => write!(f, "none"),
+        };
+        match self {
+            Answer::Single(answer) => value(f, answer),
+            Answer::ByColor(answers) => {
+                for (i, (color, answer)) in answers.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{color}: ")?;
+                    value(f, answer)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<N: Num> Query<N> {
+    fn mentions_grouped_color(&self) -> bool {
+        let grouped = |field: &Field| *field == Field::Color(ColorRef::Grouped);
+        self.field.as_ref().is_some_and(grouped)
+            || self.conditions.iter().any(|c| match c {
+                Condition::Field(field, _, _) => grouped(field),
+                Condition::Hand(_, color, _, _) => *color == ColorRef::Grouped,
+            })
+    }
+
+    fn evaluate(&self, games: &[Game<N>]) -> Answer<N> {
+        let colors = colors(games);
+        if !self.by_color {
+            return Answer::Single(self.aggregate(games, &colors, ""));
+        }
+
+        Answer::ByColor(
+            colors
+                .iter()
+                .map(|color| (color.to_string(), self.aggregate(games, &colors, color)))
+                .collect(),
+        )
+    }
+
+    // `grouped` is the color standing in for `color`
+    fn aggregate(&self, games: &[Game<N>], colors: &BTreeSet<&str>, grouped: &str) -> Option<N> {
+        let resolve = |color: &'_ ColorRef| match color {
+            ColorRef::Named(color) => color.clone(),
+            ColorRef::Grouped => grouped.to_string(),
+        };
+        let value = |game: &Game<N>, field: &Field| match field {
+            Field::Id => N::from_usize(game.id as usize),
+            Field::Power => game.power(colors),
+            Field::Hands => N::from_usize(game.hands.len()),
+            Field::Cubes => game
+                .hands
+                .iter()
+                .flat_map(|h| h.counts.values())
+                .copied()
+                .sum(),
+            Field::Color(color) => game.min_game_size().count(&resolve(color)),
+        };
+        let matches = |game: &&Game<N>| {
+            self.conditions.iter().all(|condition| match condition {
+                Condition::Field(field, cmp, n) => cmp.test(value(game, field), *n),
+                Condition::Hand(quantifier, color, cmp, n) => {
+                    let color = resolve(color);
+                    let mut hands = game.hands.iter().map(|h| cmp.test(h.count(&color), *n));
+                    match quantifier {
+                        Quantifier::Any => hands.any(|m| m),
+                        Quantifier::All => hands.all(|m| m),
+                    }
+                }
+            })
+        };
+
+        let games = games.iter().filter(matches);
+        let values = games.map(|game| match &self.field {
+            Some(field) => value(game, field),
+            None => N::ZERO,
+        });
+        match self.aggregate {
+            Aggregate::Sum => Some(values.sum()),
+            Aggregate::Max => values.max(),
+            Aggregate::Min => values.min(),
+            Aggregate::Count => Some(N::from_usize(values.count())),
+        }
+    }
+}
+
+/// Parses and answers a `Query` about the games in `input`, naming a color none of
+/// the games have is an error
+pub fn query<N: Num>(input: &str, query: &str) -> Result<Answer<N>, String> {
+    let query: Query<N> = query.parse()?;
+    let games = input
+        .lines()
+        .map(Game::from_str)
+        .collect::<Result<Vec<Game<N>>, _>>()?;
+
+    // a misspelt color would otherwise quietly count as none of it
+    let known = colors(&games);
+    if let Some(unknown) = query.named_colors().find(|c| !known.contains(c)) {
+        return Err(format!(
+            "Unknown color '{unknown}', the games have {}",
+            known.iter().copied().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(query.evaluate(&games))
+}
+
+/// How often each count of one color was revealed, reveals without it count as 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorStats<N> {
+    pub color: String,
+    pub max: N,
+    pub mean: f64,
+    pub histogram: BTreeMap<N, usize>,
+}
+
+/// A game whose minimum bag is mostly one color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dominated {
+    pub id: u32,
+    pub color: String,
+    /// That color's part of the minimum bag, from 0 to 1
+    pub share: f64,
+}
+
+/// The spread of the games' powers, quartiles by nearest rank
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerStats<N> {
+    pub min: N,
+    pub lower_quartile: N,
+    pub median: N,
+    pub upper_quartile: N,
+    pub max: N,
+    pub mean: f64,
+}
+
+/// Statistics over every reveal of every game. `Display` prints a table, `to_json` the same as JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics<N> {
+    pub reveals: usize,
+    pub colors: Vec<ColorStats<N>>,
+    /// Pearson correlation of the counts in each reveal, in the order of `colors`.
+    /// `None` when either color never changes.
+    pub correlation: Vec<Vec<Option<f64>>>,
+    pub dominated: Vec<Dominated>,
+    /// `None` without any games
+    pub power: Option<PowerStats<N>>,
+}
+
+fn correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len() as f64;
+    let (mx, my) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+    let (mut cov, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mx) * (y - my);
+        vx += (x - mx) * (x - mx);
+        vy += (y - my) * (y - my);
+    }
+    (vx > 0.0 && vy > 0.0).then(|| cov / sqrt(vx * vy))
+}
+
+// `f64::sqrt` needs std, Newton's method is plenty for a report
+fn sqrt(x: f64) -> f64 {
+    let mut root = if x > 1.0 { x } else { 1.0 };
+    for _ in 0..100 {
+        let next = (root + x / root) / 2.0;
+        if next >= root {
+            break;
+        }
+        root = next;
+    }
+    root
+}
+
+/// Statistics over every color in the input. A game is dominated when one color is
+/// more than `dominance` (from 0 to 1) of its minimum bag.
+pub fn statistics<N: Num>(input: &str, dominance: f64) -> Statistics<N> {
+    let games: Vec<Game<N>> = input.lines().map(|line| line.parse().unwrap()).collect();
+    let all_colors = colors(&games);
+    let hands: Vec<_> = games.iter().flat_map(|g| &g.hands).collect();
+
+    let counts: Vec<Vec<N>> = all_colors
+        .iter()
+        .map(|c| hands.iter().map(|h| h.count(c)).collect())
+        .collect();
+    let colors = all_colors
+        .iter()
+        .zip(&counts)
+        .map(|(color, counts)| {
+            let mut histogram = BTreeMap::new();
+            for &n in counts {
+                *histogram.entry(n).or_default() += 1;
+            }
+            let total: f64 = counts.iter().map(|n| n.to_f64()).sum();
+            ColorStats {
+                color: color.to_string(),
+                max: counts.iter().copied().max().unwrap_or_default(),
+                mean: total / hands.len() as f64,
+                histogram,
+            }
+        })
+        .collect();
+
+    let floats: Vec<Vec<f64>> = counts
+        .iter()
+        .map(|c| c.iter().map(|n| n.to_f64()).collect())
+        .collect();
+    let correlation = floats
+        .iter()
+        .map(|xs| floats.iter().map(|ys| correlation(xs, ys)).collect())
+        .collect();
+
+    let mut dominated = Vec::new();
+    for game in &games {
+        let min_size = game.min_game_size();
+        let total: f64 = min_size.counts.values().map(|n| n.to_f64()).sum();
+        let top = min_size.counts.iter().max_by_key(|(_, n)| **n);
+        if let Some((color, n)) = top.filter(|_| total > 0.0) {
+            let share = n.to_f64() / total;
+            if share > dominance {
+                dominated.push(Dominated {
+                    id: game.id,
+                    color: color.clone(),
+                    share,
+                });
+            }
+        }
+    }
+
+    let mut powers: Vec<N> = games.iter().map(|g| g.power(&all_colors)).collect();
+    powers.sort();
+    let rank = |q: usize| powers[(powers.len() * q).div_ceil(4).max(1) - 1];
+    let power = (!powers.is_empty()).then(|| PowerStats {
+        min: powers[0],
+        lower_quartile: rank(1),
+        median: rank(2),
+        upper_quartile: rank(3),
+        max: powers[powers.len() - 1],
+        mean: powers.iter().map(|p| p.to_f64()).sum::<f64>() / powers.len() as f64,
+    });
+
+    Statistics {
+        reveals: hands.len(),
+        colors,
+        correlation,
+        dominated,
+        power,
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn join<T>(items: impl IntoIterator<Item = T>, f: impl Fn(T) -> String) -> String {
+    items.into_iter().map(f).collect::<Vec<_>>().join(",")
+}
+
+impl<N: Num> Statistics<N> {
+    pub fn to_json(&self) -> String {
+        let colors = join(&self.colors, |c| {
+            let histogram = join(&c.histogram, |(n, times)| format!("\"{n}\":{times}"));
+            format!(
+                "{{\"color\":{},\"max\":{},\"mean\":{},\"histogram\":{{{histogram}}}}}",
+                json_string(&c.color),
+                c.max,
+                c.mean
+            )
+        });
+        let correlation = join(&self.correlation, |row| {
+            let row = join(row, |r| r.map_or("null".to_string(), |r| r.to_string()));
+            format!("[{row}]")
+        });
+        let dominated = join(&self.dominated, |d| {
+            format!(
+                "{{\"id\":{},\"color\":{},\"share\":{}}}",
+                d.id,
+                json_string(&d.color),
+                d.share
+            )
+        });
+        let power = match &self.power {
+            Some(p) => format!(
+                "{{\"min\":{},\"lower_quartile\":{},\"median\":{},\"upper_quartile\":{},\"max\":{},\"mean\":{}}}",
+                p.min, p.lower_quartile, p.median, p.upper_quartile, p.max, p.mean
+            ),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"reveals\":{},\"colors\":[{colors}],\"correlation\":[{correlation}],\"dominated\":[{dominated}],\"power\":{power}}}",
+            self.reveals
+        )
+    }
+}
+
+impl<N: Num> Display for Statistics<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .colors
+            .iter()
+            .map(|c| c.color.len())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+        writeln!(f, "{} reveals", self.reveals)?;
+        writeln!(
+            f,
+            "{:width$}  {:>6}  {:>6}  histogram",
+            "color", "max", "mean"
+        )?;
+        for c in &self.colors {
+            let histogram = c
+                .histogram
+                .iter()
+                .map(|(n, times)| format!("{n}:{times}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                f,
+                "{:width$}  {:>6}  {:>6.2}  {histogram}",
+                c.color, c.max, c.mean
+            )?;
+        }
+
+        write!(f, "\n{:width$}", "")?;
+        for c in &self.colors {
+            write!(f, "  {:>width$}", c.color)?;
+        }
+        writeln!(f)?;
+        for (c, row) in self.colors.iter().zip(&self.correlation) {
+            write!(f, "{:width$}", c.color)?;
+            for r in row {
+                match r {
+                    Some(r) => write!(f, "  {r:>width$.2}")?,
+                    None => write!(f, "  {:>width$}", "-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        if !self.dominated.is_empty() {
+            let dominated = self
+                .dominated
+                .iter()
+                .map(|d| format!("{} ({} {:.0}%)", d.id, d.color, d.share * 100.0))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "\ndominated games: {dominated}")?;
+        }
+        match &self.power {
+            Some(p) => write!(
+                f,
+                "\npower: min {}, quartiles {} {} {}, max {}, mean {:.2}",
+                p.min, p.lower_quartile, p.median, p.upper_quartile, p.max, p.mean
+            ),
+            None => write!(f, "\npower: no games"),
+        }
+    }
+}
+
+/// A prior over how many cubes in total a bag holds. For each total every way of
+/// splitting it between the colors is equally likely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagPrior {
+    weights: Vec<(usize, f64)>,
+}
+
+impl BagPrior {
+    pub fn uniform(totals: RangeInclusive<usize>) -> Self {
+        totals.map(|total| (total, 1.0)).collect()
+    }
+}
+
+/// (total, weight) pairs, weights needn't sum to 1
+impl FromIterator<(usize, f64)> for BagPrior {
+    fn from_iter<T: IntoIterator<Item = (usize, f64)>>(iter: T) -> Self {
+        BagPrior {
+            weights: iter.into_iter().filter(|(_, w)| *w > 0.0).collect(),
+        }
+    }
+}
+
+/// The possible contents of one game's bag, given its hands
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagEstimate {
+    pub id: u32,
+    /// The order of the counts in each bag
+    pub colors: Vec<String>,
+    /// Every possible bag with its posterior probability, most probable first
+    pub posterior: Vec<(Vec<usize>, f64)>,
+    /// The bag under which the hands seen are most likely, out of the bags whose total
+    /// the prior gives any weight, so a different prior can change it
+    pub maximum_likelihood: Vec<usize>,
+}
+
+impl BagEstimate {
+    pub fn most_probable(&self) -> &[usize] {
+        &self.posterior[0].0
+    }
+
+    /// The posterior probability of each total number of cubes
+    pub fn totals(&self) -> Vec<(usize, f64)> {
+        let mut totals: BTreeMap<usize, f64> = BTreeMap::new();
+        for (bag, p) in &self.posterior {
+            *totals.entry(bag.iter().sum()).or_default() += p;
+        }
+        totals.into_iter().collect()
+    }
+
+    pub fn expected(&self, color: &str) -> Option<f64> {
+        let i = self.colors.iter().position(|c| c == color)?;
+        Some(
+            self.posterior
+                .iter()
+                .map(|(bag, p)| bag[i] as f64 * p)
+                .sum(),
+        )
+    }
+}
+
+// n choose k
+fn choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+// Every way of splitting `total` cubes with at least `min[i]` of color `i`
+fn bags(total: usize, min: &[usize]) -> Vec<Vec<usize>> {
+    let Some((first, rest)) = min.split_first() else {
+        return if total == 0 {
+            vec![Vec::new()]
+        } else {
+            Vec::new()
+        };
+    };
+    let least_rest: usize = rest.iter().sum();
+
+    let mut bags = Vec::new();
+    for count in *first..=total.saturating_sub(least_rest) {
+        for mut bag in self::bags(total - count, rest) {
+            bag.insert(0, count);
+            bags.push(bag);
+        }
+    }
+    bags
+}
+
+impl<N: Num> Game<N> {
+    /// The chance of drawing each hand from `bag`. Cubes are drawn without replacement
+    /// within a hand, then put back before the next.
+    fn likelihood(&self, colors: &[&str], bag: &[usize]) -> f64 {
+        let total: usize = bag.iter().sum();
+        self.hands
+            .iter()
+            .map(|hand| {
+                let drawn: Vec<_> = colors.iter().map(|c| hand.count(c).to_usize()).collect();
+                let ways: f64 = drawn.iter().zip(bag).map(|(d, c)| choose(*c, *d)).product();
+                ways / choose(total, drawn.iter().sum())
+            })
+            .product()
+    }
+
+    fn estimate_bag(&self, colors: &[&str], prior: &BagPrior) -> Option<BagEstimate> {
+        if colors.is_empty() {
+            return None;
+        }
+        let min_size = self.min_game_size();
+        let min: Vec<_> = colors
+            .iter()
+            .map(|c| min_size.count(c).to_usize())
+            .collect();
+
+        let mut maximum_likelihood: Option<(Vec<usize>, f64)> = None;
+        let mut posterior = Vec::new();
+        for (total, weight) in &prior.weights {
+            // uniform over every split of the total, whether or not it fits the hands
+            let splits = choose(total + colors.len() - 1, colors.len() - 1);
+            for bag in bags(*total, &min) {
+                let likelihood = self.likelihood(colors, &bag);
+                if maximum_likelihood
+                    .as_ref()
+                    .is_none_or(|(_, best)| likelihood > *best)
+                {
+                    maximum_likelihood = Some((bag.clone(), likelihood));
+                }
+                posterior.push((bag, weight / splits * likelihood));
+            }
+        }
+
+        let evidence: f64 = posterior.iter().map(|(_, p)| p).sum();
+        if evidence <= 0.0 {
+            return None;
+        }
+        for (_, p) in &mut posterior {
+            *p /= evidence;
+        }
+        posterior.sort_by(|(a_bag, a), (b_bag, b)| b.total_cmp(a).then(a_bag.cmp(b_bag)));
+
+        Some(BagEstimate {
+            id: self.id,
+            colors: colors.iter().map(ToString::to_string).collect(),
+            posterior,
+            maximum_likelihood: maximum_likelihood?.0,
+        })
+    }
+}
+
+/// Estimates each game's bag over every color in the input, `None` for a game
+/// no bag the prior allows could have produced
+pub fn estimate_bags<N: Num>(input: &str, prior: &BagPrior) -> Vec<(u32, Option<BagEstimate>)> {
+    let games: Vec<Game<N>> = input.lines().map(|line| line.parse().unwrap()).collect();
+    let colors: Vec<_> = colors(&games).into_iter().collect();
+
+    games
+        .iter()
+        .map(|g| (g.id, g.estimate_bag(&colors, prior)))
+        .collect()
+}
+
+/// How the cubes of each reveal are taken from the bag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawPolicy {
+    /// Between 1 and `max` cubes, all different, put back after the reveal.
+    /// This is what `estimate_bags` assumes.
+    WithoutReplacement { max: usize },
+    /// Between 1 and `max` cubes, each put back before the next is drawn,
+    /// so a reveal can show more of a color than the bag holds
+    WithReplacement { max: usize },
+}
+
+/// Plays games from a known bag, reproducibly for a given seed
+#[derive(Debug, Clone)]
+pub struct CubeSimulator<N> {
+    bag: GameLimits<N>,
+    policy: DrawPolicy,
+    reveals: RangeInclusive<usize>,
+    rng: XorShift,
+    next_id: u32,
+}
+
+/// A simulated game with the bag it was drawn from
+#[derive(Debug, Clone)]
+pub struct SimulatedGame<N> {
+    pub bag: GameLimits<N>,
+    game: Game<N>,
+}
+
+impl<N: Num> SimulatedGame<N> {
+    pub fn id(&self) -> u32 {
+        self.game.id
+    }
+
+    /// The game as a line of puzzle input
+    pub fn text(&self) -> String {
+        self.game.to_string()
+    }
+
+    pub fn is_possible(&self, limits: &GameLimits<N>) -> bool {
+        !self.game.is_illegal(limits)
+    }
+
+    /// The least cubes of each color the hands need
+    pub fn min_bag(&self) -> GameLimits<N> {
+        GameLimits {
+            num_of_colors: self.game.min_game_size(),
+        }
+    }
+}
+
+impl<N: Num> CubeSimulator<N> {
+    /// Panics if the bag is empty
+    pub fn new(bag: GameLimits<N>, policy: DrawPolicy, seed: u64) -> Self {
+        assert!(bag.total() > N::ZERO, "can't draw from an empty bag");
+        CubeSimulator {
+            bag,
+            policy,
+            reveals: 1..=6,
+            rng: XorShift::new(seed),
+            next_id: 1,
+        }
+    }
+
+    /// How many reveals each game has, 1 to 6 by default
+    pub fn with_reveals(mut self, reveals: RangeInclusive<usize>) -> Self {
+        assert!(!reveals.is_empty(), "a game needs a number of reveals");
+        self.reveals = reveals;
+        self
+    }
+
+    pub fn bag(&self) -> &GameLimits<N> {
+        &self.bag
+    }
+
+    // Picks `n` cubes, each color weighted by how many of it are left
+    fn draw(&mut self, counts: &[usize], n: usize, replace: bool) -> Vec<usize> {
+        let mut left = counts.to_vec();
+        let mut drawn = vec![0; counts.len()];
+        for _ in 0..n {
+            let mut cube = self.rng.below(left.iter().sum());
+            let color = left
+                .iter()
+                .position(|&c| {
+                    let here = cube < c;
+                    cube = cube.saturating_sub(c);
+                    here
+                })
+                .unwrap();
+            drawn[color] += 1;
+            if !replace {
+                left[color] -= 1;
+            }
+        }
+        drawn
+    }
+
+    /// The next game, ids count up from 1
+    pub fn game(&mut self) -> SimulatedGame<N> {
+        let (colors, counts): (Vec<_>, Vec<_>) = self
+            .bag
+            .iter()
+            .map(|(c, n)| (c.to_string(), n.to_usize()))
+            .unzip();
+        let (max, replace) = match self.policy {
+            DrawPolicy::WithoutReplacement { max } => (max.min(counts.iter().sum()), false),
+            DrawPolicy::WithReplacement { max } => (max, true),
+        };
+
+        let reveals = self.reveals.start() + self.rng.below(self.reveals.clone().count());
+        let hands = (0..reveals)
+            .map(|_| {
+                let n = 1 + self.rng.below(max.max(1));
+                let drawn = self.draw(&counts, n, replace);
+                colors
+                    .iter()
+                    .zip(drawn)
+                    .filter(|(_, d)| *d > 0)
+                    .map(|(c, d)| (c.as_str(), N::from_usize(d)))
+                    .collect()
+            })
+            .collect();
+
+        let id = self.next_id;
+        self.next_id += 1;
+        SimulatedGame {
+            bag: self.bag.clone(),
+            game: Game { id, hands },
+        }
+    }
+
+    /// `games` games as puzzle input, one per line
+    pub fn document(&mut self, games: usize) -> String {
+        (0..games).map(|_| self.game().text() + "\n").collect()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -211,7 +1434,320 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"##;
 
-        assert_eq!(run_part_2(input), 2286)
+        assert_eq!(run_part_2::<u32>(input), 2286)
+    }
+
+    #[test]
+    fn part2_wide_input() {
+        // the power overflows a u64
+        let input = "Game 1: 3000000 blue, 4000000 red; 1 red, 2000000 green, 6 blue";
+
+        assert_eq!(run_part_2::<u128>(input), 24_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn any_colors() {
+        let input = r##"Game 1: 3 yellow, 4 red; 1 purple, 2 yellow
+Game 2: 1 yellow, 2 purple; 5 red, 2 yellow
+Game 3: 1 red
+"##;
+        let games: Vec<Game<u32>> = input.lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(games[0].min_game_size().count("yellow"), 3);
+        assert_eq!(games[0].min_game_size().count("blue"), 0);
+
+        let limits: GameLimits<u32> = [("yellow", 3), ("red", 4), ("purple", 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(run_part_1(input, limits), 1 + 3);
+        // red only, so no yellow or purple allowed
+        assert_eq!(run_part_1(input, GameLimits::from_iter([("red", 10)])), 3);
+
+        // game 3 has no yellow or purple, so no power
+        assert_eq!(run_part_2::<u32>(input), 3 * 4 + 2 * 2 * 5);
+    }
+
+    const EXAMPLE: &str = r##"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+"##;
+
+    #[test]
+    fn simulated_games_fit_their_bag() {
+        let bag = GameLimits::new(2, 3, 4);
+        let policy = DrawPolicy::WithoutReplacement { max: 9 };
+        let mut sim = CubeSimulator::new(bag.clone(), policy, 43).with_reveals(30..=30);
+        let games: Vec<_> = (0..200).map(|_| sim.game()).collect();
+
+        // the text parses back to the same game
+        let doc: String = games.iter().map(|g| g.text() + "\n").collect();
+        let parsed: Vec<Game<u32>> = doc.lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(parsed[0].to_string(), games[0].text());
+        assert_eq!(run_part_1(&doc, bag.clone()), (1..=200).sum());
+
+        // never more than the bag, and with this many reveals nearly always all of it
+        assert!(games.iter().all(|g| g.is_possible(&bag)));
+        let whole = games.iter().filter(|g| g.min_bag() == bag).count();
+        assert!(whole > 180, "{whole}");
+        // one fewer red is only possible if red never showed twice
+        let short = GameLimits::new(1, 3, 4);
+        assert!(games.iter().filter(|g| g.is_possible(&short)).count() < 10);
+    }
+
+    #[test]
+    fn simulated_replacement_overdraws() {
+        let bag = GameLimits::new(1, 1, 1);
+        let policy = DrawPolicy::WithReplacement { max: 3 };
+        let mut sim = CubeSimulator::new(bag.clone(), policy, 7).with_reveals(10..=10);
+        let doc = sim.document(100);
+        assert_eq!(doc.lines().count(), 100);
+        // a reveal has no repeated cube about 2 times in 3, ten of them in a row about 1 in 100
+        let possible = run_part_1(&doc, bag);
+        assert!(possible < (1..=100).sum::<u32>() / 20, "{possible}");
+    }
+
+    #[test]
+    fn simulated_bags_are_estimated() {
+        let bag = GameLimits::new(2, 3, 5);
+        let policy = DrawPolicy::WithoutReplacement { max: 4 };
+        let mut sim = CubeSimulator::new(bag, policy, 2023).with_reveals(20..=20);
+        let doc = sim.document(50);
+
+        // each game alone is a rough guess, but on average they're close
+        let estimates = estimate_bags::<u32>(&doc, &BagPrior::uniform(10..=10));
+        for (color, truth) in [("blue", 5.0), ("green", 3.0), ("red", 2.0)] {
+            let mean = estimates
+                .iter()
+                .map(|(_, e)| e.as_ref().unwrap().expected(color).unwrap())
+                .sum::<f64>()
+                / 50.0;
+            assert!((mean - truth).abs() < 0.3, "{color}: {mean}");
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let stats = statistics::<u32>(EXAMPLE, 0.5);
+        assert_eq!(stats.reveals, 14);
+        let red = &stats.colors[2];
+        assert_eq!((red.color.as_str(), red.max), ("red", 20));
+        assert_eq!(red.histogram[&0], 3);
+        assert!(close(red.mean, 61.0 / 14.0));
+        assert_eq!(stats.correlation[0][0], Some(1.0));
+        assert_eq!(stats.correlation[0][1], stats.correlation[1][0]);
+
+        // 20 of 39 and 6 of 11
+        let dominated: Vec<_> = stats.dominated.iter().map(|d| (d.id, &*d.color)).collect();
+        assert_eq!(dominated, [(3, "red"), (5, "red")]);
+
+        let power = stats.power.as_ref().unwrap();
+        assert_eq!((power.min, power.median, power.max), (12, 48, 1560));
+        assert!(close(power.mean, 2286.0 / 5.0));
+
+        let json = stats.to_json();
+        assert!(json.starts_with(r#"{"reveals":14,"colors":[{"color":"blue","max":15,"#));
+        assert!(json.contains(r#""dominated":[{"id":3,"color":"red","share":0.5128"#));
+        assert!(stats
+            .to_string()
+            .contains("power: min 12, quartiles 36 48 630, max 1560"));
+        assert_eq!(json_string("a\"b\\\n"), r#""a\"b\\\u000a""#);
+
+        assert_eq!(statistics::<u32>("", 0.5).power, None);
+
+        // powers and counts past usize
+        let huge = "Game 1: 1099511627776 red, 1099511627776 blue; 1 green";
+        let stats = statistics::<u128>(huge, 0.5);
+        assert!(close(stats.power.unwrap().mean, 2f64.powi(80)));
+        assert!(close(stats.colors[0].mean, 2f64.powi(39)));
+    }
+
+    #[test]
+    fn validation() {
+        let input = r##"Game 1: 3 red, 2 red; 1 blue
+Game 2: 0 green;  ; 4 blue
+Game 2: 1 red
+Game 5: 1 red
+"##;
+        // lenient parsing sums the duplicate color, but an empty reveal doesn't parse
+        assert_eq!(run_part_2::<u32>("Game 1: 3 red, 2 red"), 5);
+        assert!(validate::<u32>(input, Validation::Lenient).is_err());
+        assert!(validate::<u32>("Game 1: ; 1 red", Validation::Lenient).is_err());
+        assert!("Game 1: ; 1 red".parse::<Game<u32>>().is_err());
+        // solving reads the games as validation did
+        let limits = GameLimits::new(3, 0, 4);
+        assert_eq!(run_part_1_with(input, limits, Validation::Warn), 2 + 2 + 5);
+        assert_eq!(
+            run_part_2_with::<u32>("Game 1: ; 1 red", Validation::Warn),
+            1
+        );
+
+        let issues = validate::<u32>(input, Validation::Warn).unwrap();
+        assert_eq!(
+            issues,
+            [
+                Issue::DuplicateColor {
+                    line: 1,
+                    reveal: 1,
+                    color: "red".to_string()
+                },
+                Issue::ZeroCount {
+                    line: 2,
+                    reveal: 1,
+                    color: "green".to_string()
+                },
+                Issue::EmptyReveal { line: 2, reveal: 2 },
+                Issue::DuplicateId { line: 3, id: 2 },
+                Issue::OutOfSequence {
+                    line: 4,
+                    id: 5,
+                    expected: Some(3)
+                },
+            ]
+        );
+
+        let err = validate::<u32>(input, Validation::Strict).unwrap_err();
+        assert_eq!(err.lines().count(), 5);
+        assert_eq!(
+            err.lines().next(),
+            Some("line 1: 'red' given twice in reveal 1")
+        );
+
+        assert_eq!(validate::<u32>(EXAMPLE, Validation::Strict), Ok(Vec::new()));
+        assert!(validate::<u32>("Game x: 1 red", Validation::Warn).is_err());
+        assert!(validate::<u32>("Game 1: red", Validation::Lenient).is_err());
+        // too many for the solver's counts
+        assert!(validate::<u8>("Game 1: 300 red", Validation::Lenient).is_err());
+
+        let last = "Game 4294967295: 1 red\nGame 1: 1 red";
+        let issues = validate::<u32>(last, Validation::Warn).unwrap();
+        assert_eq!(
+            issues[1].to_string(),
+            "line 2: game 1, expected no more games"
+        );
+    }
+
+    #[test]
+    fn queries() {
+        let ask = |q| query::<u32>(EXAMPLE, q).map(|a| a.to_string());
+
+        assert_eq!(ask("sum id where any hand blue > 10"), Ok("4".to_string()));
+        assert_eq!(ask("max power where hands >= 3"), Ok("1560".to_string()));
+        assert_eq!(
+            ask("count where all hands red <= 6 and id != 2"),
+            Ok("2".to_string())
+        );
+        assert_eq!(ask("sum power"), Ok("2286".to_string()));
+        assert_eq!(ask("min cubes where red > 100"), Ok("none".to_string()));
+        assert_eq!(
+            ask("max color by color"),
+            Ok("blue: 15, green: 13, red: 20".to_string())
+        );
+        assert_eq!(
+            ask("count where any hand color >= 6 by color"),
+            Ok("blue: 3, green: 1, red: 3".to_string())
+        );
+
+        assert!(ask("avg id").is_err());
+        assert!(ask("sum where id > 1").is_err());
+        assert!(ask("sum id where blue").is_err());
+        assert!(ask("sum id where any blue > 1").is_err());
+        assert!(ask("sum color").is_err());
+        assert!(ask("sum id by colour").is_err());
+        assert!(ask("sum id where id > x").is_err());
+        assert_eq!(
+            ask("sum id where any hand bleu > 1"),
+            Err("Unknown color 'bleu', the games have blue, green, red".to_string())
+        );
+        assert!(ask("max bleu").is_err());
+    }
+
+    #[test]
+    fn frontier() {
+        // each of games 1, 2 and 5 alone, the others need more than one of them
+        assert_eq!(
+            limit_frontier::<u32>(EXAMPLE, 1),
+            [
+                GameLimits::new(6, 3, 2),
+                GameLimits::new(1, 3, 4),
+                GameLimits::new(4, 2, 6)
+            ]
+        );
+        assert_eq!(
+            limit_frontier::<u32>(EXAMPLE, 0),
+            [GameLimits::new(0, 0, 0)]
+        );
+        assert_eq!(
+            limit_frontier::<u32>(EXAMPLE, 5),
+            [GameLimits::new(20, 13, 15)]
+        );
+
+        for limit in limit_frontier::<u32>(EXAMPLE, 3) {
+            let legal = EXAMPLE
+                .lines()
+                .map(|l| l.parse::<Game<u32>>().unwrap())
+                .filter(|g| !g.is_illegal(&limit))
+                .count();
+            assert_eq!(legal, 3);
+        }
+    }
+
+    #[test]
+    fn smallest_and_sweep() {
+        let (smallest, total) = smallest_limits::<u32>(EXAMPLE, &[1, 2]).unwrap();
+        assert_eq!(smallest, GameLimits::new(4, 3, 6));
+        assert_eq!(total, 13);
+        assert_eq!(smallest.iter().count(), 3);
+        assert_eq!(smallest_limits::<u32>(EXAMPLE, &[1, 9]), None);
+
+        assert_eq!(
+            sweep::<u32>(EXAMPLE, [0, 4, 6, 14, 15, 20]),
+            [(0, 0), (4, 1), (6, 3), (14, 3), (15, 4), (20, 5)]
+        );
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn bag_posterior() {
+        // A bag of 3 holding at least 2 red: red, red, blue or all red,
+        // equally likely beforehand but all red makes the hand 3 times as likely
+        let input = "Game 1: 2 red\nGame 2: 1 blue";
+        let estimates = estimate_bags::<u32>(input, &BagPrior::uniform(3..=3));
+        let red = estimates[0].1.as_ref().unwrap();
+
+        assert_eq!(red.colors, ["blue", "red"]);
+        assert_eq!(red.posterior.len(), 2);
+        assert_eq!(red.most_probable(), [0, 3]);
+        assert!(close(red.posterior[0].1, 0.75));
+        assert_eq!(red.posterior[1].0, [1, 2]);
+        assert!(close(red.posterior[1].1, 0.25));
+        assert_eq!(red.maximum_likelihood, [0, 3]);
+        assert!(close(red.expected("red").unwrap(), 2.75));
+    }
+
+    #[test]
+    fn bag_hands_are_replaced() {
+        // drawing 2 blue twice only needs 2 blue in the bag
+        let input = "Game 7: 2 blue; 2 blue, 1 red";
+        let prior: BagPrior = [(3, 1.0), (4, 3.0), (100, 0.0)].into_iter().collect();
+        let (id, estimate) = &estimate_bags::<u32>(input, &prior)[0];
+        let estimate = estimate.as_ref().unwrap();
+
+        assert_eq!(*id, 7);
+        // 1/3 * 1 for [2, 1], but 3/6 * 3/4 for [3, 1]
+        assert_eq!(estimate.maximum_likelihood, [3, 1]);
+        let totals = estimate.totals();
+        assert_eq!(totals.len(), 2);
+        assert!(close(totals.iter().map(|(_, p)| p).sum(), 1.0));
+        // every bag of 3 or 4 with at least 2 blue and 1 red
+        assert_eq!(estimate.posterior.len(), 1 + 2);
+
+        // too many cubes for any bag the prior allows
+        let estimates = estimate_bags::<u32>("Game 1: 5 red", &BagPrior::uniform(1..=4));
+        assert_eq!(estimates[0].1, None);
     }
 
     #[test]
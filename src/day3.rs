@@ -1,22 +1,32 @@
-use std::collections::{HashMap, HashSet};
+use crate::{
+    num::Num,
+    visualize::{Canvas, Rgb},
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+pub fn run_part_1<N: Num>(input: &str) -> N {
+    part_sum(input, &SchematicRules::default())
+}
 
-fn main() {
-    let input = include_str!("../assets/day3Input.txt");
-    let res_1 = run_part_1(input);
-    println!("part 1: {res_1}");
-    let res_2 = run_part_2(input);
-    println!("part 2: {res_2}");
+pub fn run_part_2<N: Num>(input: &str) -> N {
+    gear_ratio_sum(input, &SchematicRules::default())
 }
 
-fn run_part_1(input: &str) -> u32 {
-    let potential_parts = read_part_numbers(input);
+/// The sum of every number next to a symbol
+pub fn part_sum<N: Num>(input: &str, rules: &SchematicRules) -> N {
+    let potential_parts = read_part_numbers::<N>(input);
 
-    let char_array = input.lines().map(|l| l.chars().collect()).collect();
+    let char_array: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
 
     potential_parts
         .iter()
         .filter_map(|p| {
-            if p.is_part(&char_array) {
+            if p.is_part(&char_array, rules) {
                 Some(p.number)
             } else {
                 None
@@ -25,206 +35,662 @@ fn run_part_1(input: &str) -> u32 {
         .sum()
 }
 
-fn run_part_2(input: &str) -> u32 {
-    let potential_parts = read_part_numbers(input);
+/// The sum of the products of each pair of numbers sharing a gear
+pub fn gear_ratio_sum<N: Num>(input: &str, rules: &SchematicRules) -> N {
+    SymbolIndex::new(input, rules).total(
+        |c| rules.is_gear(c),
+        PartCount::Exactly(2),
+        Combine::Product,
+    )
+}
+
+/// Which symbols to combine, by how many numbers touch them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartCount {
+    Exactly(usize),
+    AtLeast(usize),
+}
 
-    let char_array = input.lines().map(|l| l.chars().collect()).collect();
+impl PartCount {
+    fn matches(self, parts: usize) -> bool {
+        match self {
+            PartCount::Exactly(n) => parts == n,
+            PartCount::AtLeast(n) => parts >= n,
+        }
+    }
+}
 
-    let mut gears: HashMap<Gear, Vec<u32>> = HashMap::new();
+/// How to combine the numbers touching a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
 
-    for p in potential_parts {
-        let part_gears = p.gears(&char_array);
-        for gear in part_gears {
-            gears
-                .entry(gear)
-                .and_modify(|rs| rs.push(p.number))
-                .or_insert(vec![p.number]);
+impl Combine {
+    fn apply<N: Num>(self, parts: impl Iterator<Item = N>) -> N {
+        match self {
+            Combine::Product => parts.product(),
+            Combine::Sum => parts.sum(),
+            Combine::Max => parts.max().unwrap_or_default(),
         }
     }
+}
 
-    gears
-        .iter()
-        .filter_map(|(_g, parts)| {
-            if parts.len() == 2 {
-                Some(parts[0] * parts[1])
-            } else {
-                None
-            }
-        })
-        .sum()
+/// One symbol with the numbers touching it and their combined value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolValue<N> {
+    pub row: usize,
+    pub column: usize,
+    pub symbol: char,
+    /// In reading order
+    pub parts: Vec<NumberNode<N>>,
+    pub value: N,
 }
 
+/// Every symbol in a schematic with the numbers touching it,
+/// built once and then combined however the puzzle asks
 #[derive(Debug, Clone, PartialEq)]
-struct PartNumber {
-    index: usize,
-    line_num: usize,
-    length: usize,
-    number: u32,
+pub struct SymbolIndex<N> {
+    symbols: BTreeMap<Gear, (char, Vec<NumberNode<N>>)>,
 }
 
-fn is_symbol(c: char) -> bool {
-    c.is_ascii_punctuation() && c != '.'
+impl<N: Num> SymbolIndex<N> {
+    pub fn new(input: &str, rules: &SchematicRules) -> Self {
+        let char_array: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+
+        let mut symbols: BTreeMap<Gear, (char, Vec<NumberNode<N>>)> = BTreeMap::new();
+        for p in read_part_numbers::<N>(input) {
+            for cell in p.symbols(&char_array, rules) {
+                symbols
+                    .entry(cell)
+                    .or_insert_with(|| (char_array[cell.row][cell.column], Vec::new()))
+                    .1
+                    .push(p.node());
+            }
+        }
+
+        SymbolIndex { symbols }
+    }
+
+    /// Each symbol accepted by `which` with the right number of parts, in reading order.
+    /// Symbols touching no numbers aren't in the index.
+    pub fn aggregate(
+        &self,
+        which: impl Fn(char) -> bool,
+        count: PartCount,
+        combine: Combine,
+    ) -> Vec<SymbolValue<N>> {
+        self.symbols
+            .iter()
+            .filter(|(_, (symbol, parts))| which(*symbol) && count.matches(parts.len()))
+            .map(|(cell, (symbol, parts))| SymbolValue {
+                row: cell.row,
+                column: cell.column,
+                symbol: *symbol,
+                parts: parts.clone(),
+                value: combine.apply(parts.iter().map(|n| n.value)),
+            })
+            .collect()
+    }
+
+    /// The sum of `aggregate`'s values
+    pub fn total(&self, which: impl Fn(char) -> bool, count: PartCount, combine: Combine) -> N {
+        self.aggregate(which, count, combine)
+            .iter()
+            .map(|s| s.value)
+            .sum()
+    }
 }
 
-fn is_gear(c: char) -> bool {
-    c == '*'
+/// A number and where it is, in the adjacency graph or touching a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberNode<N> {
+    pub span: Span,
+    pub value: N,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Gear {
-    row: usize,
-    column: usize,
+/// A symbol cell in the adjacency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolNode {
+    pub span: Span,
+    pub symbol: char,
+    pub gear: bool,
 }
 
-impl PartNumber {
-    fn is_part(&self, input: &Vec<Vec<char>>) -> bool {
-        // for simplicity I'm assuming the input is rectangular
-        // which it is for the AOC input
+/// Indices into a graph's numbers and symbols, each in reading order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Component {
+    pub numbers: Vec<usize>,
+    pub symbols: Vec<usize>,
+}
 
-        let left_start = if self.index == 0 { 0 } else { self.index - 1 };
-        // non-inclusive
-        let right_end = (self.index + self.length + 1).min(input[0].len());
+/// The bipartite graph of numbers and the symbols they touch
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartGraph<N> {
+    pub numbers: Vec<NumberNode<N>>,
+    pub symbols: Vec<SymbolNode>,
+    /// (number, symbol) pairs of indices, in reading order of the numbers
+    pub edges: Vec<(usize, usize)>,
+}
 
-        // top row
-        if self.line_num != 0 {
-            for i in left_start..right_end {
-                if is_symbol(input[self.line_num - 1][i]) {
-                    return true;
+impl<N: Num> PartGraph<N> {
+    pub fn new(input: &str, rules: &SchematicRules) -> Self {
+        let char_array: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+
+        let (mut numbers, mut symbols) = (Vec::new(), Vec::new());
+        // the numbers again, as the parts code reads them
+        let mut parts = Vec::new();
+        let mut cells = BTreeMap::new();
+        for token in tokens::<N>(input, rules) {
+            match token {
+                Token::Number { span, value } => {
+                    parts.extend(PartNumber::from_token(token));
+                    numbers.push(NumberNode { span, value });
+                }
+                Token::Symbol { span, symbol } => {
+                    let cell = Gear {
+                        row: span.row,
+                        column: span.start,
+                    };
+                    cells.insert(cell, symbols.len());
+                    symbols.push(SymbolNode {
+                        span,
+                        symbol,
+                        gear: rules.is_gear(symbol),
+                    });
                 }
             }
         }
 
-        // left
-        if self.index != 0 {
-            if is_symbol(input[self.line_num][self.index - 1]) {
-                return true;
-            }
+        let mut edges = Vec::new();
+        for (i, p) in parts.iter().enumerate() {
+            edges.extend(p.symbols(&char_array, rules).iter().map(|c| (i, cells[c])));
+        }
+
+        PartGraph {
+            numbers,
+            symbols,
+            edges,
         }
+    }
 
-        //right
-        if self.index + self.length < input[self.line_num].len() {
-            if is_symbol(input[self.line_num][self.index + self.length]) {
-                return true;
+    // The root of each node's set, numbers first then symbols
+    fn roots(&self) -> Vec<usize> {
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
             }
+            i
+        }
+
+        let offset = self.numbers.len();
+        let mut parent: Vec<_> = (0..offset + self.symbols.len()).collect();
+        for (n, s) in &self.edges {
+            let (a, b) = (find(&mut parent, *n), find(&mut parent, offset + s));
+            parent[a.max(b)] = a.min(b);
         }
+        (0..parent.len()).map(|i| find(&mut parent, i)).collect()
+    }
 
-        // bottom row
-        if self.line_num + 1 < input.len() {
-            for i in left_start..right_end {
-                if is_symbol(input[self.line_num + 1][i]) {
-                    return true;
+    /// Every group of connected numbers and symbols with at least one edge,
+    /// ordered by their first number
+    pub fn components(&self) -> Vec<Component> {
+        let roots = self.roots();
+        let offset = self.numbers.len();
+        let mut components: BTreeMap<usize, Component> = BTreeMap::new();
+        for &(n, _) in &self.edges {
+            components.entry(roots[n]).or_default();
+        }
+        for (i, root) in roots.iter().enumerate() {
+            if let Some(component) = components.get_mut(root) {
+                if i < offset {
+                    component.numbers.push(i);
+                } else {
+                    component.symbols.push(i - offset);
                 }
             }
         }
-
-        return false;
+        components.into_values().collect()
     }
 
-    fn gears(&self, input: &Vec<Vec<char>>) -> HashSet<Gear> {
-        // for simplicity I'm assuming the input is rectangular
-        // which it is for the one input this has to work for
+    /// Numbers touching no symbol
+    pub fn isolated_numbers(&self) -> Vec<usize> {
+        let connected: BTreeSet<_> = self.edges.iter().map(|(n, _)| *n).collect();
+        (0..self.numbers.len())
+            .filter(|n| !connected.contains(n))
+            .collect()
+    }
 
-        let mut gears = HashSet::new();
+    /// Symbols touching no number
+    pub fn isolated_symbols(&self) -> Vec<usize> {
+        let connected: BTreeSet<_> = self.edges.iter().map(|(_, s)| *s).collect();
+        (0..self.symbols.len())
+            .filter(|s| !connected.contains(s))
+            .collect()
+    }
 
-        let left_start: usize = if self.index == 0 { 0 } else { self.index - 1 };
-        // non-inclusive
-        let right_end = (self.index + self.length + 1).min(input[0].len());
+    /// Graphviz DOT, numbers are ellipses, symbols boxes and gears filled yellow
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph schematic {\n");
+        for (i, n) in self.numbers.iter().enumerate() {
+            dot += &format!("    n{i} [label=\"{}\"];\n", n.value);
+        }
+        for (i, s) in self.symbols.iter().enumerate() {
+            let label = match s.symbol {
+                '"' | '\\' => format!("\\{}", s.symbol),
+                c => c.to_string(),
+            };
+            let style = if s.gear {
+                ", style=filled, fillcolor=yellow"
+            } else {
+                ""
+            };
+            dot += &format!("    s{i} [label=\"{label}\", shape=box{style}];\n");
+        }
+        for (n, s) in &self.edges {
+            dot += &format!("    n{n} -- s{s};\n");
+        }
+        dot + "}\n"
+    }
+}
 
-        // top row
-        if self.line_num != 0 {
-            for i in left_start..right_end {
-                if is_gear(input[self.line_num - 1][i]) {
-                    gears.insert(Gear {
-                        row: self.line_num - 1,
-                        column: i,
-                    });
-                }
+/// Part numbers are green, other numbers grey, symbols red and gears yellow
+pub fn render_schematic(input: &str) -> Canvas {
+    let rules = SchematicRules::default();
+    let char_array: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+    let width = char_array.iter().map(|l| l.len()).max().unwrap_or(0);
+    let mut canvas = Canvas::new(width, char_array.len(), Rgb::BLACK);
+
+    for (row, line) in char_array.iter().enumerate() {
+        for (col, c) in line.iter().enumerate() {
+            if rules.is_gear(*c) {
+                canvas.set(row, col, Rgb::YELLOW);
+            } else if rules.is_symbol(*c) {
+                canvas.set(row, col, Rgb::RED);
             }
         }
+    }
+
+    for p in read_part_numbers::<u128>(input) {
+        let color = if p.is_part(&char_array, &rules) {
+            Rgb::GREEN
+        } else {
+            Rgb::GREY
+        };
+        for col in p.index..p.index + p.length {
+            canvas.set(p.line_num, col, color);
+        }
+    }
 
-        // left
-        if self.index != 0 {
-            if is_gear(input[self.line_num][self.index - 1]) {
-                gears.insert(Gear {
-                    row: self.line_num,
-                    column: self.index - 1,
-                });
+    canvas
+}
+
+/// What the window scanner finds, as soon as it's sure of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<N> {
+    Part {
+        row: usize,
+        column: usize,
+        number: N,
+    },
+    /// A gear touching exactly two numbers, with their product
+    Gear { row: usize, column: usize, ratio: N },
+}
+
+/// Scans a schematic a line at a time, holding only the previous, current and next lines.
+/// Each line's parts and gears are reported once the line after it has been pushed.
+#[derive(Debug, Clone)]
+pub struct WindowScanner<'r, N> {
+    rules: &'r SchematicRules,
+    // missing lines before the first and after the last are empty, so blank
+    cells: [Vec<char>; 3],
+    numbers: [Vec<PartNumber<N>>; 3],
+    lines: usize,
+}
+
+impl<'r, N: Num> WindowScanner<'r, N> {
+    pub fn new(rules: &'r SchematicRules) -> Self {
+        WindowScanner {
+            rules,
+            cells: Default::default(),
+            numbers: Default::default(),
+            lines: 0,
+        }
+    }
+
+    pub fn push(&mut self, line: &str, emit: impl FnMut(Event<N>)) {
+        self.shift(line.chars().collect());
+        self.lines += 1;
+        if self.lines > 1 {
+            self.scan_middle(self.lines - 2, emit);
+        }
+    }
+
+    /// Reports the last line
+    pub fn finish(mut self, emit: impl FnMut(Event<N>)) {
+        if self.lines > 0 {
+            self.shift(Vec::new());
+            self.scan_middle(self.lines - 1, emit);
+        }
+    }
+
+    fn shift(&mut self, line: Vec<char>) {
+        self.cells.rotate_left(1);
+        self.numbers.rotate_left(1);
+        self.numbers[2] = line_tokens(&line, 2, self.rules)
+            .into_iter()
+            .filter_map(PartNumber::from_token)
+            .collect();
+        self.cells[2] = line;
+        for (line_num, numbers) in self.numbers.iter_mut().enumerate() {
+            for p in numbers {
+                p.line_num = line_num;
             }
         }
+    }
 
-        //right
-        if self.index + self.length < input[self.line_num].len() {
-            if is_gear(input[self.line_num][self.index + self.length]) {
-                gears.insert(Gear {
-                    row: self.line_num,
-                    column: self.index + self.length,
+    // The middle line is `row` of the schematic
+    fn scan_middle(&self, row: usize, mut emit: impl FnMut(Event<N>)) {
+        for p in &self.numbers[1] {
+            if p.is_part(&self.cells, self.rules) {
+                emit(Event::Part {
+                    row,
+                    column: p.index,
+                    number: p.number,
                 });
             }
         }
 
-        // bottom row
-        if self.line_num + 1 < input.len() {
-            for i in left_start..right_end {
-                if is_gear(input[self.line_num + 1][i]) {
-                    gears.insert(Gear {
-                        row: self.line_num + 1,
-                        column: i,
-                    });
+        // the numbers touching each gear on the middle line
+        let mut gears: BTreeMap<usize, Vec<N>> = BTreeMap::new();
+        for p in self.numbers.iter().flatten() {
+            for cell in p.symbols(&self.cells, self.rules) {
+                if cell.row == 1 && self.rules.is_gear(self.cells[1][cell.column]) {
+                    gears.entry(cell.column).or_default().push(p.number);
                 }
             }
         }
+        for (column, numbers) in gears {
+            if let [a, b] = numbers[..] {
+                emit(Event::Gear {
+                    row,
+                    column,
+                    ratio: a * b,
+                });
+            }
+        }
+    }
+}
 
-        return gears;
+/// Both parts' answers in one pass over the lines, in constant memory for bounded line lengths
+pub fn stream_sums<N: Num, S: AsRef<str>>(
+    lines: impl IntoIterator<Item = S>,
+    rules: &SchematicRules,
+) -> (N, N) {
+    let (mut parts, mut ratios) = (N::ZERO, N::ZERO);
+    let mut add = |event| match event {
+        Event::Part { number, .. } => parts += number,
+        Event::Gear { ratio, .. } => ratios += ratio,
+    };
+
+    let mut scanner = WindowScanner::new(rules);
+    for line in lines {
+        scanner.push(line.as_ref(), &mut add);
     }
+    scanner.finish(&mut add);
+    (parts, ratios)
 }
 
-// Gets all sets of horizontally adjacent numbers from the string
-fn read_part_numbers(input: &str) -> Vec<PartNumber> {
-    let digits: Vec<_> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(line_num, line)| {
-            let m = line.match_indices(char::is_numeric);
-            let line_num_iter = vec![line_num; m.clone().count()];
-            m.zip(line_num_iter)
-        })
-        .collect();
-
-    // this is patently un-rustlike, but idk what else to do
-    let mut numbers = Vec::new();
-
-    let mut i = 0;
-    while i < digits.len() - 1 {
-        let mut running_number = String::new();
-        let ((start_index, digit), line_num) = digits[i];
-        let mut index = start_index;
-        running_number += digit;
-        for j in i + 1..digits.len() {
-            i += 1;
-            let ((index_next, digit_next), line_num_next) = digits[j];
-            if line_num_next == line_num && index_next == index + 1 {
-                running_number += digit_next;
-                index += 1;
-            } else {
-                break;
+/// `stream_sums` straight from a reader, never holding more than three lines
+#[cfg(feature = "std")]
+pub fn stream_sums_from<N: Num>(
+    reader: impl std::io::BufRead,
+    rules: &SchematicRules,
+) -> std::io::Result<(N, N)> {
+    let mut result = Ok(());
+    let lines = reader.lines().map_while(|line| match line {
+        Ok(line) => Some(line),
+        Err(e) => {
+            result = Err(e);
+            None
+        }
+    });
+    let sums = stream_sums(lines, rules);
+    result.map(|_| sums)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PartNumber<N> {
+    index: usize,
+    line_num: usize,
+    length: usize,
+    number: N,
+}
+
+/// Which characters are symbols
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SymbolSet {
+    AsciiPunctuation,
+    /// Anything but blanks, digits and whitespace
+    Any,
+    Only(BTreeSet<char>),
+}
+
+/// How to read a schematic: which characters are blank, which are symbols and which
+/// of those are gears. By default `.` is blank, any other ASCII punctuation is a symbol
+/// and `*` is a gear. Digits are never blank or symbols, gears are always symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicRules {
+    blanks: BTreeSet<char>,
+    symbols: SymbolSet,
+    gears: BTreeSet<char>,
+}
+
+impl Default for SchematicRules {
+    fn default() -> Self {
+        SchematicRules {
+            blanks: BTreeSet::from(['.']),
+            symbols: SymbolSet::AsciiPunctuation,
+            gears: BTreeSet::from(['*']),
+        }
+    }
+}
+
+impl SchematicRules {
+    pub fn with_blanks(mut self, blanks: impl IntoIterator<Item = char>) -> Self {
+        self.blanks = blanks.into_iter().collect();
+        self
+    }
+
+    /// Only these are symbols, along with the gears
+    pub fn with_symbols(mut self, symbols: impl IntoIterator<Item = char>) -> Self {
+        self.symbols = SymbolSet::Only(symbols.into_iter().collect());
+        self
+    }
+
+    /// Every character that isn't blank, numeric ('7', '٣', '½') or whitespace is a symbol,
+    /// Unicode included. Numeric characters given to `with_gears` still count.
+    pub fn with_any_symbol(mut self) -> Self {
+        self.symbols = SymbolSet::Any;
+        self
+    }
+
+    pub fn with_gears(mut self, gears: impl IntoIterator<Item = char>) -> Self {
+        self.gears = gears.into_iter().collect();
+        self
+    }
+
+    pub fn is_blank(&self, c: char) -> bool {
+        self.blanks.contains(&c) && !c.is_ascii_digit()
+    }
+
+    pub fn is_symbol(&self, c: char) -> bool {
+        if c.is_ascii_digit() || self.blanks.contains(&c) {
+            return false;
+        }
+        self.gears.contains(&c)
+            || match &self.symbols {
+                SymbolSet::AsciiPunctuation => c.is_ascii_punctuation(),
+                SymbolSet::Any => !c.is_whitespace() && !c.is_numeric(),
+                SymbolSet::Only(symbols) => symbols.contains(&c),
+            }
+    }
+
+    pub fn is_gear(&self, c: char) -> bool {
+        self.gears.contains(&c) && self.is_symbol(c)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Gear {
+    row: usize,
+    column: usize,
+}
+
+impl<N> PartNumber<N> {
+    // The cells around the number, lines may differ in length and missing cells are blank
+    fn neighbours<'a>(&self, input: &'a [Vec<char>]) -> impl Iterator<Item = (Gear, char)> + 'a {
+        let (line_num, index, length) = (self.line_num, self.index, self.length);
+        let columns = index.saturating_sub(1)..=index + length;
+
+        (line_num.saturating_sub(1)..=line_num + 1)
+            .flat_map(move |row| columns.clone().map(move |column| Gear { row, column }))
+            .filter(move |cell| {
+                cell.row != line_num || cell.column < index || cell.column >= index + length
+            })
+            .filter_map(|cell| Some((cell, *input.get(cell.row)?.get(cell.column)?)))
+    }
+
+    fn is_part(&self, input: &[Vec<char>], rules: &SchematicRules) -> bool {
+        self.neighbours(input).any(|(_, c)| rules.is_symbol(c))
+    }
+
+    /// Every symbol cell touching the number, gears included
+    fn symbols(&self, input: &[Vec<char>], rules: &SchematicRules) -> BTreeSet<Gear> {
+        self.neighbours(input)
+            .filter(|(_, c)| rules.is_symbol(*c))
+            .map(|(cell, _)| cell)
+            .collect()
+    }
+}
+
+/// Where a token is, columns `start..end` of `row`, counting chars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A run of digits or a single symbol, blanks and anything else aren't tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<N> {
+    Number { span: Span, value: N },
+    Symbol { span: Span, symbol: char },
+}
+
+// The tokens of one line, in order
+fn line_tokens<N: Num>(line: &[char], row: usize, rules: &SchematicRules) -> Vec<Token<N>> {
+    let mut tokens = Vec::new();
+    let mut number_start = None;
+    // one past the end ends a number there
+    for (column, c) in line.iter().copied().map(Some).chain([None]).enumerate() {
+        let is_digit = c.is_some_and(|c| c.is_ascii_digit());
+        match number_start {
+            None if is_digit => number_start = Some(column),
+            Some(start) if !is_digit => {
+                let digits: String = line[start..column].iter().collect();
+                tokens.push(Token::Number {
+                    span: Span {
+                        row,
+                        start,
+                        end: column,
+                    },
+                    value: digits.parse().unwrap(),
+                });
+                number_start = None;
             }
+            _ => {}
+        }
+        if let Some(c) = c.filter(|c| rules.is_symbol(*c)) {
+            tokens.push(Token::Symbol {
+                span: Span {
+                    row,
+                    start: column,
+                    end: column + 1,
+                },
+                symbol: c,
+            });
         }
+    }
+    tokens
+}
+
+/// Every number and symbol in reading order. Panics if a number doesn't fit in `N`.
+pub fn tokens<'a, N: Num + 'a>(
+    input: &'a str,
+    rules: &'a SchematicRules,
+) -> impl Iterator<Item = Token<N>> + 'a {
+    input.lines().enumerate().flat_map(|(row, line)| {
+        let line: Vec<char> = line.chars().collect();
+        line_tokens(&line, row, rules)
+    })
+}
 
-        numbers.push(PartNumber {
-            index: start_index,
-            line_num,
-            length: running_number.len(),
-            number: u32::from_str_radix(&running_number, 10).unwrap(),
-        });
+impl<N: Copy> PartNumber<N> {
+    fn node(&self) -> NumberNode<N> {
+        NumberNode {
+            span: Span {
+                row: self.line_num,
+                start: self.index,
+                end: self.index + self.length,
+            },
+            value: self.number,
+        }
     }
+}
 
-    numbers
+impl<N> PartNumber<N> {
+    fn from_token(token: Token<N>) -> Option<Self> {
+        match token {
+            Token::Number { span, value } => Some(PartNumber {
+                index: span.start,
+                line_num: span.row,
+                length: span.end - span.start,
+                number: value,
+            }),
+            Token::Symbol { .. } => None,
+        }
+    }
+}
+
+// Gets all sets of horizontally adjacent numbers from the string
+fn read_part_numbers<N: Num>(input: &str) -> Vec<PartNumber<N>> {
+    // numbers are the same whatever the symbols
+    tokens(input, &SchematicRules::default())
+        .filter_map(PartNumber::from_token)
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    const EXAMPLE: &str = r##"467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+"##;
+
     #[test]
     fn part1_known_input() {
         let input = r##"467..114..
@@ -238,7 +704,7 @@ mod test {
 ...$.*....
 .664.598..
 "##;
-        assert_eq!(run_part_1(input), 4361);
+        assert_eq!(run_part_1::<u32>(input), 4361);
     }
 
     #[test]
@@ -254,7 +720,7 @@ mod test {
 ...$.*....
 .664.598..
 "##;
-        assert_eq!(run_part_2(input), 467835);
+        assert_eq!(run_part_2::<u32>(input), 467835);
     }
 
     #[test]
@@ -263,7 +729,7 @@ mod test {
 ......33
 ........
 "##;
-        assert_eq!(run_part_1(input), 467);
+        assert_eq!(run_part_1::<u32>(input), 467);
     }
 
     #[test]
@@ -343,6 +809,179 @@ mod test {
             },
         ];
 
-        assert_eq!(read_part_numbers(input), expected)
+        assert_eq!(read_part_numbers::<u32>(input), expected)
+    }
+
+    #[test]
+    fn custom_rules() {
+        // '#' is blank, so 633 isn't a part, '§' is only a symbol when asked for
+        let input = "467~~114~~\n~~~×~~~~~~\n~~35~~633~\n~~~~~~#~~~\n~~§~~~~~~~\n~~~12~~~~~\n";
+        let rules = SchematicRules::default().with_blanks(['~', '#']);
+        assert_eq!(part_sum::<u32>(input, &rules), 0);
+
+        let rules = rules.with_any_symbol().with_gears(['×']);
+        assert!(rules.is_gear('×') && rules.is_symbol('§'));
+        assert!(!rules.is_symbol('#') && !rules.is_symbol('7') && !rules.is_symbol('٣'));
+        assert_eq!(part_sum::<u32>(input, &rules), 467 + 35 + 12);
+        assert_eq!(gear_ratio_sum::<u32>(input, &rules), 467 * 35);
+
+        // asked for by name, a numeric character is a gear like any other
+        let rules = SchematicRules::default().with_gears(['½']);
+        assert!(rules.is_symbol('½') && rules.is_gear('½'));
+        assert_eq!(gear_ratio_sum::<u32>("2.3\n.½.\n", &rules), 6);
+        assert_eq!(part_sum::<u32>("2.3\n.½.\n", &rules), 5);
+
+        let rules = SchematicRules::default().with_symbols(['$']).with_gears([]);
+        assert_eq!(part_sum::<u32>("1$.\n..*2\n", &rules), 1);
+        assert!(!rules.is_gear('*'));
+    }
+
+    #[test]
+    fn symbol_aggregation() {
+        let index = SymbolIndex::<u32>::new(EXAMPLE, &SchematicRules::default());
+        let any = |_| true;
+
+        let lonely = index.aggregate(any, PartCount::Exactly(1), Combine::Sum);
+        let lonely: Vec<_> = lonely.iter().map(|s| (s.symbol, s.value)).collect();
+        assert_eq!(lonely, [('#', 633), ('*', 617), ('+', 592), ('$', 664)]);
+
+        let gears = index.aggregate(|c| c == '*', PartCount::AtLeast(2), Combine::Max);
+        assert_eq!((gears[0].row, gears[0].column), (1, 3));
+        let parts: Vec<_> = gears[0].parts.iter().map(|n| (n.value, n.span)).collect();
+        let span = |row, start, end| Span { row, start, end };
+        assert_eq!(parts, [(467, span(0, 0, 3)), (35, span(2, 2, 4))]);
+        assert_eq!(gears[1].value, 755);
+
+        assert_eq!(
+            index.total(any, PartCount::AtLeast(1), Combine::Sum),
+            run_part_1::<u32>(EXAMPLE)
+        );
+        assert_eq!(index.total(any, PartCount::Exactly(3), Combine::Product), 0);
+    }
+
+    #[test]
+    fn ragged_lines() {
+        // symbols past the end of the line above or below, and lines shorter than the number
+        let input = "1\n.23*\n\n4\n#\n..56\n7.......*\n...\n$\n89";
+        assert_eq!(run_part_1::<u32>(input), 23 + 4 + 89);
+        let input = "..*\n\n12\n.*\n3..\n...5\n";
+        assert_eq!(run_part_1::<u32>(input), 12 + 3);
+        assert_eq!(run_part_2::<u32>(input), 12 * 3);
+        assert_eq!(run_part_1::<u32>("5\n.......\n*\n"), 0);
+    }
+
+    #[test]
+    fn window_scanner() {
+        let rules = SchematicRules::default();
+        assert_eq!(
+            stream_sums::<u32, _>(EXAMPLE.lines(), &rules),
+            (4361, 467835)
+        );
+
+        let ragged = "..*\n\n12\n.*\n3..\n...5";
+        assert_eq!(stream_sums::<u32, _>(ragged.lines(), &rules), (15, 36));
+        assert_eq!(stream_sums::<u32, _>("".lines(), &rules), (0, 0));
+
+        let mut events = Vec::new();
+        let mut scanner = WindowScanner::<u32>::new(&rules);
+        scanner.push("1*2", |e| events.push(e));
+        assert!(events.is_empty());
+        scanner.finish(|e| events.push(e));
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[2],
+            Event::Gear {
+                row: 0,
+                column: 1,
+                ratio: 2
+            }
+        );
+
+        #[cfg(feature = "std")]
+        assert_eq!(
+            stream_sums_from::<u32>(EXAMPLE.as_bytes(), &rules).unwrap(),
+            (4361, 467835)
+        );
+    }
+
+    #[test]
+    fn tokenizer_edges() {
+        // no digits used to underflow, a lone last digit used to be dropped
+        assert_eq!(run_part_1::<u32>("...\n.*.\n"), 0);
+        assert_eq!(run_part_1::<u32>(""), 0);
+        assert_eq!(run_part_1::<u32>("12.\n..*\n..7"), 12 + 7);
+        assert_eq!(run_part_2::<u32>("1*\n.2"), 2);
+
+        let rules = SchematicRules::default();
+        let found: Vec<Token<u32>> = tokens("\n7é#\n..42", &rules).collect();
+        let span = |row, start, end| Span { row, start, end };
+        assert_eq!(
+            found,
+            [
+                Token::Number {
+                    span: span(1, 0, 1),
+                    value: 7
+                },
+                Token::Symbol {
+                    span: span(1, 2, 3),
+                    symbol: '#'
+                },
+                Token::Number {
+                    span: span(2, 2, 4),
+                    value: 42
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn adjacency_graph() {
+        // with a symbol touching nothing
+        let input = EXAMPLE.replace(".664.598..", ".664.598.@");
+        let graph = PartGraph::<u32>::new(&input, &SchematicRules::default());
+        assert_eq!((graph.numbers.len(), graph.symbols.len()), (10, 7));
+
+        let values = |numbers: &[usize]| -> Vec<u32> {
+            numbers.iter().map(|n| graph.numbers[*n].value).collect()
+        };
+        let components = graph.components();
+        assert_eq!(components.len(), 6);
+        assert_eq!(values(&components[0].numbers), [467, 35]);
+        assert_eq!(values(&components[4].numbers), [755, 598]);
+        assert!(graph.symbols[components[4].symbols[0]].gear);
+
+        assert_eq!(values(&graph.isolated_numbers()), [114, 58]);
+        assert_eq!(graph.symbols[graph.isolated_symbols()[0]].symbol, '@');
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("graph schematic {\n    n0 [label=\"467\"];\n"));
+        assert!(dot.contains("s0 [label=\"*\", shape=box, style=filled, fillcolor=yellow];"));
+        assert!(dot.contains("    n0 -- s0;\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn render_colors() {
+        let input = r##"467..114..
+...*......
+..35..633.
+......#...
+"##;
+        let canvas = render_schematic(input);
+
+        assert_eq!(canvas.get(0, 0), Some(Rgb::GREEN));
+        assert_eq!(canvas.get(0, 5), Some(Rgb::GREY));
+        assert_eq!(canvas.get(1, 3), Some(Rgb::YELLOW));
+        assert_eq!(canvas.get(3, 6), Some(Rgb::RED));
+        assert_eq!(canvas.get(3, 0), Some(Rgb::BLACK));
+    }
+
+    #[test]
+    fn part2_wide_numbers() {
+        let input = r##"12345678901234.....
+..............*....
+...........98765432109
+"##;
+        assert_eq!(run_part_2::<u128>(input), 12345678901234 * 98765432109);
     }
 }
@@ -1,30 +1,29 @@
+use crate::num::Num;
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::str::FromStr;
-use std::collections::HashSet;
 
-fn main() {
-    let input = include_str!("../assets/day4Input.txt");
-    let part1_res = run_part_1(input);
-    println!("part 1: {part1_res}");
-
-    let part2_res = run_part_2(input);
-    println!("part 2: {part2_res}");
-}
-
-fn run_part_1(input: &str) -> u32 {
-    let cards = Card::read_input(input).unwrap();
+pub fn run_part_1<N: Num>(input: &str) -> N {
+    let cards = Card::<N>::read_input(input).unwrap();
 
     cards.iter().map(Card::score).sum()
 }
 
-fn run_part_2(input: &str) -> u32 {
-    let cards = Card::read_input(input).unwrap();
+pub fn run_part_2<N: Num>(input: &str) -> N {
+    let cards = Card::<N>::read_input(input).unwrap();
 
-    let mut instances_of_card = vec![1; cards.len()];
+    let mut instances_of_card = vec![N::ONE; cards.len()];
     for (i, card) in cards.iter().enumerate() {
         let num_matches = card.num_matches() as usize;
         // the game asserts that the number of matches will not make a card produce past the end
         for j in (i + 1)..(i + num_matches + 1) {
-            instances_of_card[j] += instances_of_card[i];
+            let instances = instances_of_card[i];
+            instances_of_card[j] += instances;
         }
     }
 
@@ -32,24 +31,24 @@ fn run_part_2(input: &str) -> u32 {
 }
 
 #[derive(Debug, Clone)]
-struct Card {
+struct Card<N> {
     _id: u32,
-    winning: HashSet<u32>,
-    ours: HashSet<u32>,
+    winning: BTreeSet<N>,
+    ours: BTreeSet<N>,
 }
 
-impl Card {
-    fn read_input(input: &str) -> Result<Vec<Card>, String> {
+impl<N: Num> Card<N> {
+    fn read_input(input: &str) -> Result<Vec<Card<N>>, String> {
         input.lines().map(|l| l.parse()).collect()
     }
 
-    fn score(&self) -> u32 {
+    fn score(&self) -> N {
         let num_matches = self.num_matches();
 
         if num_matches >= 1 {
-            2_usize.pow(num_matches - 1) as u32
+            (N::ONE + N::ONE).pow(num_matches - 1)
         } else {
-            0
+            N::ZERO
         }
     }
 
@@ -58,7 +57,7 @@ impl Card {
     }
 }
 
-impl FromStr for Card {
+impl<N: Num> FromStr for Card<N> {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -76,9 +75,9 @@ impl FromStr for Card {
         if header_split.len() != 2 {
             return Err("Header must be of format 'Card <id>:'".to_string());
         }
-        let id = u32::from_str_radix(header_split[1], 10).or_else(|e| {
-            return Err(format!("Card id must be a number: {e}"));
-        })?;
+        let id = header_split[1]
+            .parse::<u32>()
+            .map_err(|e| format!("Card id must be a number: {e}"))?;
 
         let body_split: Vec<_> = body.split('|').collect();
         if body_split.len() != 2 {
@@ -86,11 +85,11 @@ impl FromStr for Card {
         }
         let winning = body_split[0]
             .split_ascii_whitespace()
-            .map(|n| u32::from_str_radix(n, 10).unwrap())
+            .map(|n| n.parse().unwrap())
             .collect();
         let ours = body_split[1]
             .split_ascii_whitespace()
-            .map(|n| u32::from_str_radix(n, 10).unwrap())
+            .map(|n| n.parse().unwrap())
             .collect();
 
         Ok(Card {
@@ -115,7 +114,7 @@ Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 "##;
 
-        assert_eq!(run_part_1(input), 13)
+        assert_eq!(run_part_1::<u32>(input), 13)
     }
 
     #[test]
@@ -128,6 +127,16 @@ Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 "##;
 
-        assert_eq!(run_part_2(input), 30)
+        assert_eq!(run_part_2::<u32>(input), 30)
+    }
+
+    #[test]
+    fn part1_wide_score() {
+        let numbers: Vec<_> = (1..=41).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let input = format!("Card 1: {numbers} | {numbers}");
+
+        // 41 matches doubles past what a u32 can hold
+        assert_eq!(run_part_1::<u64>(&input), 1 << 40)
     }
 }
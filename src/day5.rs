@@ -1,27 +1,21 @@
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{
     ops::{Add, Sub},
     str::{FromStr, Split},
 };
-use std::{collections::HashSet, time::Instant};
-
-fn main() {
-    let input = include_str!("../assets/day5Input.txt");
-    let part_1_out = run_part_1(input);
-    let now = Instant::now();
-    println!("part_1: {part_1_out}, in {:?}", now.elapsed());
-
-    let now = Instant::now();
-    let part_2_out = run_part_2(input);
-    println!("part_2: {part_2_out}, in {:?}", now.elapsed());
-}
 
-fn run_part_1(input: &str) -> usize {
+pub fn run_part_1(input: &str) -> usize {
     let almanac: Almanac = input.parse().unwrap();
 
     almanac.smallest_location().into()
 }
 
-fn run_part_2(input: &str) -> usize {
+pub fn run_part_2(input: &str) -> usize {
     let almanac: Almanac = input.parse().unwrap();
 
     almanac.smallest_range_location().into()
@@ -150,7 +144,7 @@ where
     S: From<L>,
     D: From<L>,
     L: FromStr,
-    <L as FromStr>::Err: std::fmt::Display,
+    <L as FromStr>::Err: core::fmt::Display,
 {
     type Err = String;
 
@@ -214,7 +208,7 @@ where
     S: From<L>,
     D: From<L>,
     L: FromStr,
-    <L as FromStr>::Err: std::fmt::Display,
+    <L as FromStr>::Err: core::fmt::Display,
 {
     type Err = String;
 
@@ -265,7 +259,7 @@ impl Almanac {
     }
 
     fn smallest_range_location(&self) -> Location {
-        let mut map_discontinuities = HashSet::new();
+        let mut map_discontinuities = BTreeSet::new();
         for range in &self.seed_ranges {
             map_discontinuities.insert(range.start);
         }
@@ -321,7 +315,7 @@ impl FromStr for Almanac {
         }
         let seeds: Vec<Seed> = seeds_str
             .map(|s| {
-                let n = usize::from_str_radix(s, 10).map_err(|e| format!("{e}"))?;
+                let n = s.parse().map_err(|e| format!("{e}"))?;
                 Ok(Seed(n))
             })
             .collect::<Result<Vec<_>, String>>()?;
@@ -365,7 +359,7 @@ where
     S: From<L>,
     D: From<L>,
     L: FromStr,
-    <L as FromStr>::Err: std::fmt::Display,
+    <L as FromStr>::Err: core::fmt::Display,
 {
     let maps: Maps<S, D, L> = stanzas
         .next()
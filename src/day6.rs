@@ -1,15 +1,13 @@
+use crate::num::Num;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::str::FromStr;
 
-fn main() {
-    let input = include_str!("../assets/day6Input.txt");
-    let part_1_res = run_part_1(input);
-    println!("part 1: {part_1_res}");
-    let part_2_res = run_part_2(input);
-    println!("part 2: {part_2_res}");
-}
-
-fn run_part_1(input: &str) -> usize {
-    let competition: Competition = input.parse().unwrap();
+pub fn run_part_1<N: Num>(input: &str) -> N {
+    let competition: Competition<N> = input.parse().unwrap();
 
     competition
         .races
@@ -18,46 +16,51 @@ fn run_part_1(input: &str) -> usize {
         .product()
 }
 
-fn run_part_2(input: &str) -> usize {
-    let race: Race = input.parse().unwrap();
+pub fn run_part_2<N: Num>(input: &str) -> N {
+    let race: Race<N> = input.parse().unwrap();
 
     race.num_ways_to_beat()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Race {
-    time: usize,
-    record: usize,
+struct Race<N> {
+    time: N,
+    record: N,
 }
 
-impl Race {
-    fn _distance_traveled(&self, time_held: usize) -> usize {
+impl<N: Num> Race<N> {
+    fn distance_traveled(&self, time_held: N) -> N {
         if time_held >= self.time {
-            return 0;
+            return N::ZERO;
         }
         time_held * (self.time - time_held)
     }
 
-    fn num_ways_to_beat(&self) -> usize {
+    fn num_ways_to_beat(&self) -> N {
         // The distance traveled is just `f(x) = (t - x) * x = -x^2 - tx` where t is the race time
         // We can simply find the 0s of the function `g(x) = f(x) - r = -x^2 - tx - r` where r is the record
         // (-b +/- sqrt(b^2 - 4ac) ) / (2a)
+        // Using the integer square root keeps this exact for any width of N,
+        // it can land the lower root up to 1 short so we step onto the first winning hold time
 
-        let diff = f64::sqrt((self.time.pow(2) - 4 * self.record) as f64);
-        let lower = (-(self.time as f64) + diff) / (-2.0);
-        let upper = (-(self.time as f64) - diff) / (-2.0);
+        let two = N::ONE + N::ONE;
+        // the furthest we can go is holding for half the race
+        if self.distance_traveled(self.time / two) <= self.record {
+            return N::ZERO;
+        }
 
-        let r1 = upper - lower;
-        let range = upper.floor() - lower.ceil();
-        if range == r1 {
-            range as usize - 1
-        } else {
-            range as usize + 1
+        let diff = (self.time * self.time - two * two * self.record).isqrt();
+        let mut lower = (self.time - diff) / two;
+        while self.distance_traveled(lower) <= self.record {
+            lower += N::ONE;
         }
+
+        // f is symmetric about t/2, so the last winning hold time is `t - lower`
+        self.time - two * lower + N::ONE
     }
 }
 
-impl FromStr for Race {
+impl<N: Num> FromStr for Race<N> {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lines: Vec<_> = s.lines().collect();
@@ -90,11 +93,11 @@ impl FromStr for Race {
     }
 }
 
-struct Competition {
-    races: Vec<Race>,
+struct Competition<N> {
+    races: Vec<Race<N>>,
 }
 
-impl FromStr for Competition {
+impl<N: Num> FromStr for Competition<N> {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -141,7 +144,7 @@ mod test {
 Distance:  9  40  200
 "##;
 
-        assert_eq!(run_part_1(input), 288);
+        assert_eq!(run_part_1::<usize>(input), 288);
     }
 
     #[test]
@@ -150,6 +153,25 @@ Distance:  9  40  200
 Distance:  9  40  200
 "##;
 
-        assert_eq!(run_part_2(input), 71503);
+        assert_eq!(run_part_2::<usize>(input), 71503);
+    }
+
+    #[test]
+    fn part_2_wide_answer() {
+        // time^2 overflows a u64
+        let input = r##"Time:      7  15   30000000000
+Distance:  9  40  2000000000
+"##;
+
+        assert_eq!(run_part_2::<u128>(input), 71529999999999);
+    }
+
+    #[test]
+    fn record_cannot_be_beaten() {
+        let race = Race::<i128> { time: 6, record: 9 };
+        assert_eq!(race.num_ways_to_beat(), 0);
+
+        let race = Race::<u8> { time: 6, record: 8 };
+        assert_eq!(race.num_ways_to_beat(), 1);
     }
 }
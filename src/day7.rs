@@ -1,34 +1,32 @@
+use crate::num::Num;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{cmp::Ordering, fmt::Debug, str::FromStr};
 
-fn main() {
-    let input = include_str!("../assets/day7Input.txt");
-    let part_1 = run_part_1(input);
-    println!("part 1: {part_1}");
-    let part_2 = run_part_2(input);
-    println!("part 2: {part_2}");
-}
-
-fn run_part_1(input: &str) -> usize {
-    let mut game: Game<Card> = input.parse().unwrap();
+pub fn run_part_1<N: Num>(input: &str) -> N {
+    let mut game: Game<Card, N> = input.parse().unwrap();
 
     game.0.sort_unstable();
 
     game.0
         .iter()
         .enumerate()
-        .map(|(i, h)| h.bid * (1 + i))
+        .map(|(i, h)| h.bid * N::from_usize(1 + i))
         .sum()
 }
 
-fn run_part_2(input: &str) -> usize {
-    let mut game: Game<JokerCard> = input.parse().unwrap();
+pub fn run_part_2<N: Num>(input: &str) -> N {
+    let mut game: Game<JokerCard, N> = input.parse().unwrap();
 
     game.0.sort_unstable();
 
     game.0
         .iter()
         .enumerate()
-        .map(|(i, h)| h.bid * (1 + i))
+        .map(|(i, h)| h.bid * N::from_usize(1 + i))
         .sum()
 }
 
@@ -137,19 +135,19 @@ impl From<&[Card; 5]> for HandType {
         }
         let max = appearances.iter().max().unwrap();
         match max {
-            1 => return HandType::HighCard,
+            1 => HandType::HighCard,
             2 => match appearances.iter().filter(|a| **a == 2).count() {
-                1 => return HandType::OnePair,
-                2 => return HandType::TwoPair,
+                1 => HandType::OnePair,
+                2 => HandType::TwoPair,
                 _ => panic!("unreachable"),
             },
             3 => match appearances.iter().filter(|a| **a == 2).count() {
-                1 => return HandType::FullHouse,
-                0 => return HandType::ThreeOfAKind,
+                1 => HandType::FullHouse,
+                0 => HandType::ThreeOfAKind,
                 _ => panic!("unreachable"),
             },
-            4 => return HandType::FourOfAKind,
-            5 => return HandType::FiveOfAKind,
+            4 => HandType::FourOfAKind,
+            5 => HandType::FiveOfAKind,
             _ => panic!("unreachable"),
         }
     }
@@ -164,7 +162,7 @@ impl From<&[JokerCard; 5]> for HandType {
         let num_jokers = appearances[0];
         let max = appearances[1..].iter().max().unwrap();
         match max + num_jokers {
-            1 => return HandType::HighCard,
+            1 => HandType::HighCard,
             2 => {
                 let jokerless_pairs = appearances.iter().filter(|a| **a == 2).count();
                 let pairs = if num_jokers != 0 {
@@ -173,8 +171,8 @@ impl From<&[JokerCard; 5]> for HandType {
                     jokerless_pairs
                 };
                 match pairs {
-                    1 => return HandType::OnePair,
-                    2 => return HandType::TwoPair,
+                    1 => HandType::OnePair,
+                    2 => HandType::TwoPair,
                     _ => panic!("unreachable"),
                 }
             }
@@ -186,28 +184,29 @@ impl From<&[JokerCard; 5]> for HandType {
                     jokerless_pairs
                 };
                 match pairs {
-                    1 => return HandType::FullHouse,
-                    0 => return HandType::ThreeOfAKind,
+                    1 => HandType::FullHouse,
+                    0 => HandType::ThreeOfAKind,
                     _ => panic!("unreachable"),
                 }
             }
-            4 => return HandType::FourOfAKind,
-            5 => return HandType::FiveOfAKind,
+            4 => HandType::FourOfAKind,
+            5 => HandType::FiveOfAKind,
             _ => panic!("unreachable"),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Hand<C> {
+struct Hand<C, N> {
     cards: [C; 5],
-    bid: usize,
+    bid: N,
     hand_type: HandType,
 }
 
-impl<C> PartialOrd for Hand<C>
+impl<C, N> PartialOrd for Hand<C, N>
 where
     C: PartialOrd,
+    N: PartialEq,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match self.hand_type.partial_cmp(&other.hand_type) {
@@ -223,18 +222,20 @@ where
     }
 }
 
-impl<C> Ord for Hand<C>
+impl<C, N> Ord for Hand<C, N>
 where
     C: Ord,
+    N: Eq,
 {
     fn cmp(&self, other: &Self) -> Ordering {
         self.partial_cmp(other).unwrap()
     }
 }
 
-impl<C> FromStr for Hand<C>
+impl<C, N> FromStr for Hand<C, N>
 where
     C: FromStr<Err = String> + Debug,
+    N: Num,
     for<'a> HandType: From<&'a [C; 5]>,
 {
     type Err = String;
@@ -255,7 +256,7 @@ where
             ));
         }
 
-        let bid: usize = split
+        let bid: N = split
             .next()
             .ok_or_else(|| "expected at least 1 word in line".to_string())?
             .parse()
@@ -271,16 +272,17 @@ where
     }
 }
 
-struct Game<C>(Vec<Hand<C>>);
+struct Game<C, N>(Vec<Hand<C, N>>);
 
-impl<C> FromStr for Game<C>
+impl<C, N> FromStr for Game<C, N>
 where
     C: FromStr<Err = String> + Debug,
+    N: Num,
     for<'a> HandType: From<&'a [C; 5]>,
 {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let hands: Vec<Hand<C>> = s
+        let hands: Vec<Hand<C, N>> = s
             .lines()
             .map(|l| l.parse())
             .collect::<Result<_, String>>()?;
@@ -301,7 +303,7 @@ KTJJT 220
 QQQJA 483
 "##;
 
-        assert_eq!(run_part_1(input), 6440);
+        assert_eq!(run_part_1::<usize>(input), 6440);
     }
 
     #[test]
@@ -313,6 +315,15 @@ KTJJT 220
 QQQJA 483
 "##;
 
-        assert_eq!(run_part_2(input), 5905);
+        assert_eq!(run_part_2::<usize>(input), 5905);
+    }
+
+    #[test]
+    fn part_1_wide_bids() {
+        let input = r##"32T3K 12345678901234567890
+KK677 2
+"##;
+
+        assert_eq!(run_part_1::<u128>(input), 12345678901234567894);
     }
 }
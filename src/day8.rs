@@ -1,14 +1,10 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::str::FromStr;
-use std::collections::HashMap;
-
-fn main() {
-    let input = include_str!("../assets/day8Input.txt");
-    let part_1 = run_part_1(input);
-    println!("part 1: {part_1}");
-
-    let part_2 = run_part_2(input);
-    println!("part 2: {part_2}");
-}
 
 fn gcd(a: usize, b: usize) -> usize {
     let (mut r_n_1, mut r_n) = if a > b { (a, b) } else { (b, a) };
@@ -26,13 +22,13 @@ fn lcm(a: usize, b: usize) -> usize {
     a * b / gcd(a, b)
 }
 
-fn run_part_1(input: &str) -> usize {
+pub fn run_part_1(input: &str) -> usize {
     let map: Map = input.parse().unwrap();
 
     map.follow_instructions()
 }
 
-fn run_part_2(input: &str) -> usize {
+pub fn run_part_2(input: &str) -> usize {
     let map: Map = input.parse().unwrap();
 
     map.follow_ghost_instructions()
@@ -95,11 +91,11 @@ impl FromStr for Node {
             .split(", ");
         let left = edges
             .next()
-            .ok_or_else(|| "expected 1 ', ' in the node line")?
+            .ok_or("expected 1 ', ' in the node line")?
             .to_string();
         let right = edges
             .next()
-            .ok_or_else(|| "expected 1 ', ' in the node line")?
+            .ok_or("expected 1 ', ' in the node line")?
             .to_string();
         Ok(Node { name, left, right })
     }
@@ -108,7 +104,7 @@ impl FromStr for Node {
 #[derive(Debug, Clone)]
 struct Map {
     instructions: Vec<Instruction>,
-    nodes: HashMap<String, Node>,
+    nodes: BTreeMap<String, Node>,
 }
 
 impl Map {
@@ -169,7 +165,7 @@ impl Map {
             .keys()
             .filter(|k| k.ends_with('A'))
             .map(|s| self.follow_node_until_ghost_stops(s))
-            .reduce(|acc, n| lcm(acc, n))
+            .reduce(lcm)
             .unwrap()
     }
 }
@@ -182,21 +178,21 @@ impl FromStr for Map {
 
         let instruction_str = stanzas
             .next()
-            .ok_or_else(|| format!("Expected at least 1 stanza"))?;
+            .ok_or_else(|| "Expected at least 1 stanza".to_string())?;
         let instructions: Vec<Instruction> = instruction_str
             .chars()
             .map(|c| c.to_string().parse())
             .collect::<Result<_, _>>()?;
 
-        let nodes: HashMap<String, Node> = stanzas
+        let nodes: BTreeMap<String, Node> = stanzas
             .next()
-            .ok_or_else(|| format!("Expected 2 stanzas"))?
+            .ok_or_else(|| "Expected 2 stanzas".to_string())?
             .lines()
             .map(|l| {
                 let node: Node = l.parse()?;
                 Ok((node.name.clone(), node))
             })
-            .collect::<Result<HashMap<_, _>, String>>()?;
+            .collect::<Result<BTreeMap<_, _>, String>>()?;
 
         Ok(Map {
             instructions,
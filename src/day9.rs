@@ -1,40 +1,34 @@
+use crate::num::Num;
+use alloc::{format, string::String, vec::Vec};
 use core::str::FromStr;
 
-fn main() {
-    let input = include_str!("../assets/day9Input.txt");
-    let part_1 = run_part_1(input);
-    println!("part 1: {part_1}");
-
-    let part_2 = run_part_2(input);
-    println!("part 2: {part_2}");
-}
-
-fn run_part_1(input: &str) -> isize {
+// N has to be signed, the differences can go negative
+pub fn run_part_1<N: Num>(input: &str) -> N {
     input
         .lines()
         .map(|l| {
-            let h: History = l.parse()?;
+            let h: History<N> = l.parse()?;
             Ok(h.next())
         })
-        .sum::<Result<isize, String>>()
+        .sum::<Result<N, String>>()
         .unwrap()
 }
 
-fn run_part_2(input: &str) -> isize {
+pub fn run_part_2<N: Num>(input: &str) -> N {
     input
         .lines()
         .map(|l| {
-            let h: History = l.parse()?;
+            let h: History<N> = l.parse()?;
             Ok(h.previous())
         })
-        .sum::<Result<isize, String>>()
+        .sum::<Result<N, String>>()
         .unwrap()
 }
 
 #[derive(Debug, Clone)]
-struct History(Vec<isize>);
+struct History<N>(Vec<N>);
 
-impl History {
+impl<N: Num> History<N> {
     fn diffs(&self) -> Self {
         let mut diffs = History(Vec::with_capacity(self.0.len() - 1));
         for i in 1..self.0.len() {
@@ -43,30 +37,30 @@ impl History {
         diffs
     }
 
-    fn next(&self) -> isize {
+    fn next(&self) -> N {
         let diffs = self.diffs();
-        if diffs.0.iter().all(|d| *d == 0) {
+        if diffs.0.iter().all(|d| *d == N::ZERO) {
             return self.0[0];
         }
         self.0[self.0.len() - 1] + diffs.next()
     }
 
-    fn previous(&self) -> isize {
+    fn previous(&self) -> N {
         let diffs = self.diffs();
 
-        if diffs.0.iter().all(|d| *d == 0) {
+        if diffs.0.iter().all(|d| *d == N::ZERO) {
             return self.0[0];
         }
         self.0[0] - diffs.previous()
     }
 }
 
-impl FromStr for History {
+impl<N: Num> FromStr for History<N> {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let nums = s
             .split_ascii_whitespace()
-            .map(|d| d.parse::<isize>().or_else(|e| Err(format!("{e}"))))
+            .map(|d| d.parse::<N>().map_err(|e| format!("{e}")))
             .collect::<Result<_, String>>()?;
         Ok(History(nums))
     }
@@ -83,7 +77,7 @@ mod test {
 10 13 16 21 30 45
 "##;
 
-        assert_eq!(run_part_1(input), 114);
+        assert_eq!(run_part_1::<isize>(input), 114);
     }
 
     #[test]
@@ -91,6 +85,14 @@ mod test {
         let input = r##"10  13  16  21  30  45
 "##;
 
-        assert_eq!(run_part_2(input), 5);
+        assert_eq!(run_part_2::<isize>(input), 5);
+    }
+
+    #[test]
+    fn part_1_wide_input() {
+        let input = r##"-9223372036854775808 -18446744073709551616 -27670116110564327424
+"##;
+
+        assert_eq!(run_part_1::<i128>(input), -36893488147419103232);
     }
 }
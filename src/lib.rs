@@ -0,0 +1,27 @@
+//! The solvers only need `core` and `alloc`, building without the default `std` feature
+//! leaves out the printing, file output and the runner.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod aho_corasick;
+#[cfg(feature = "std")]
+pub mod alloc_profile;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod num;
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod runner;
+pub mod visualize;
@@ -0,0 +1,142 @@
+use core::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    iter::{Product, Sum},
+    num::ParseIntError,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// The integer operations the solvers need, so each day can be run with
+/// whichever width (`u32`, `u64`, `u128`, `i128`...) its input calls for
+pub trait Num:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + Ord
+    + Hash
+    + FromStr<Err = ParseIntError>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + Sum
+    + Product
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Panics if `n` doesn't fit in `Self`
+    fn from_usize(n: usize) -> Self;
+
+    /// Panics if `self` is negative or doesn't fit in a `usize`
+    fn to_usize(self) -> usize;
+
+    /// The nearest `f64`, for statistics, never panics
+    fn to_f64(self) -> f64;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    fn pow(self, exp: u32) -> Self;
+
+    /// The largest integer whose square is no greater than `self`, panics if `self` is negative
+    fn isqrt(self) -> Self;
+
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn from_usize(n: usize) -> Self {
+                    <$t>::try_from(n)
+                        .unwrap_or_else(|_| panic!("{n} does not fit in {}", stringify!($t)))
+                }
+
+                fn to_usize(self) -> usize {
+                    usize::try_from(self)
+                        .unwrap_or_else(|_| panic!("{self} does not fit in usize"))
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn pow(self, exp: u32) -> Self {
+                    <$t>::pow(self, exp)
+                }
+
+                fn isqrt(self) -> Self {
+                    <$t>::isqrt(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sum_of_squares<N: Num>(input: &str) -> N {
+        input
+            .split_ascii_whitespace()
+            .map(|n| n.parse::<N>().unwrap().pow(2))
+            .sum()
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(sum_of_squares::<u32>("1 2 3"), 14);
+        assert_eq!(sum_of_squares::<i128>("-1 2 -3"), 14);
+        // 2^63 squared overflows anything smaller
+        assert_eq!(sum_of_squares::<u128>("9223372036854775808"), 1 << 126);
+    }
+
+    #[test]
+    fn isqrt_and_abs_diff() {
+        assert_eq!(Num::isqrt(26u64), 5);
+        assert_eq!(Num::isqrt(25i128), 5);
+        assert_eq!(Num::abs_diff(3i64, -4), 7);
+        assert_eq!(Num::abs_diff(3u8, 10), 7);
+    }
+
+    #[test]
+    fn to_f64() {
+        assert_eq!(Num::to_f64(-3i8), -3.0);
+        assert_eq!(Num::to_f64(1u128 << 120), 2f64.powi(120));
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_usize_overflow() {
+        u8::from_usize(256);
+    }
+}
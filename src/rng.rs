@@ -0,0 +1,55 @@
+/// A small seeded xorshift generator, the same seed always gives the same sequence.
+/// Not for anything needing good randomness, only reproducible test data.
+#[derive(Debug, Clone)]
+pub struct XorShift(u64);
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        // xorshift never leaves zero, and small seeds take a while to mix
+        XorShift(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform enough in `0..n`, panics if `n` is 0
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// True with probability `1 / n`
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let a: Vec<_> = (0..5)
+            .scan(XorShift::new(7), |r, _| Some(r.next_u64()))
+            .collect();
+        let b: Vec<_> = (0..5)
+            .scan(XorShift::new(7), |r, _| Some(r.next_u64()))
+            .collect();
+        assert_eq!(a, b);
+        assert_ne!(XorShift::new(0).next_u64(), 0);
+
+        let mut rng = XorShift::new(1);
+        assert!((0..1000).all(|_| rng.below(6) < 6));
+    }
+}
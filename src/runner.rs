@@ -0,0 +1,188 @@
+use crate::{
+    alloc_profile::{self, AllocStats},
+    cache::{self, Cache, CacheKey},
+};
+use core::fmt::{Debug, Display};
+use std::time::{Duration, Instant};
+
+// Modules any day may use, an edit to one could change any answer
+const SHARED_SOURCES: [&str; 4] = [
+    include_str!("aho_corasick.rs"),
+    include_str!("num.rs"),
+    include_str!("rng.rs"),
+    include_str!("visualize.rs"),
+];
+
+/// How a part's answer was arrived at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solved {
+    Cached,
+    Ran {
+        elapsed: Duration,
+        /// Only counted when built with the `alloc-profile` feature
+        allocs: Option<AllocStats>,
+    },
+}
+
+/// Runs and reports each part of a day, consulting the answer cache first.
+///
+/// `solver` identifies the version of the solving code. Passing the day's module and bin
+/// sources (`include_str!("../dayN.rs")` and `include_str!("dayN.rs")`) means any edit to
+/// them, or to the modules every day shares, invalidates its cached answers. Anything else
+/// the answers depend on goes in `with_parameters`.
+/// `--no-cache` on the command line skips the cache entirely, as does building with
+/// `alloc-profile` since a cached answer has nothing to profile.
+#[derive(Debug)]
+pub struct Runner<'a> {
+    day: u8,
+    input: &'a str,
+    input_hash: u64,
+    solver_hash: u64,
+    cache: Option<Cache>,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(day: u8, input: &'a str, solver: &[&str]) -> Self {
+        let sources: Vec<u8> = solver
+            .iter()
+            .chain(&SHARED_SOURCES)
+            .flat_map(|s| s.bytes().chain([0]))
+            .collect();
+        let runner = Runner {
+            day,
+            input,
+            input_hash: cache::hash(input.as_bytes()),
+            solver_hash: cache::hash(&sources),
+            cache: None,
+        };
+        if alloc_profile::ENABLED || std::env::args().any(|a| a == "--no-cache") {
+            return runner;
+        }
+
+        match Cache::open(cache::default_path(day)) {
+            Ok(cache) => runner.with_cache(cache),
+            Err(e) => {
+                eprintln!("unable to open answer cache: {e}");
+                runner
+            }
+        }
+    }
+
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Adds settings the answers depend on, such as limits or modes, to the cache key
+    pub fn with_parameters(mut self, parameters: impl Debug) -> Self {
+        let key = format!("{:016x}{parameters:?}", self.solver_hash);
+        self.solver_hash = cache::hash(key.as_bytes());
+        self
+    }
+
+    /// For when solving has side effects that a cached answer would skip
+    pub fn without_cache(mut self) -> Self {
+        self.cache = None;
+        self
+    }
+
+    /// Solves (or looks up) one part, printing and returning its answer
+    pub fn part<T: Display>(&mut self, part: u8, solve: impl FnOnce(&'a str) -> T) -> String {
+        let (answer, solved) = self.solve(part, solve);
+        report(&format!("part {part}"), &answer, solved);
+        answer
+    }
+
+    /// Runs something other than a part, never cached, printing and returning its answer
+    pub fn run<T: Display>(&mut self, label: &str, solve: impl FnOnce(&'a str) -> T) -> String {
+        let (answer, solved) = self.measure(solve);
+        report(label, &answer, solved);
+        answer
+    }
+
+    fn measure<T: Display>(&self, solve: impl FnOnce(&'a str) -> T) -> (String, Solved) {
+        let now = Instant::now();
+        let (answer, allocs) = alloc_profile::measure(|| solve(self.input));
+        let elapsed = now.elapsed();
+        let allocs = alloc_profile::ENABLED.then_some(allocs);
+        (answer.to_string(), Solved::Ran { elapsed, allocs })
+    }
+
+    pub fn solve<T: Display>(
+        &mut self,
+        part: u8,
+        solve: impl FnOnce(&'a str) -> T,
+    ) -> (String, Solved) {
+        let key = CacheKey {
+            day: self.day,
+            part,
+            input: self.input_hash,
+            solver: self.solver_hash,
+        };
+        if let Some(answer) = self.cache.as_ref().and_then(|c| c.get(&key)) {
+            return (answer.to_string(), Solved::Cached);
+        }
+
+        let (answer, solved) = self.measure(solve);
+        if let Some(cache) = &mut self.cache {
+            cache.insert(key, answer.clone());
+            if let Err(e) = cache.save() {
+                eprintln!("unable to save answer cache: {e}");
+            }
+        }
+        (answer, solved)
+    }
+}
+
+fn report(label: &str, answer: &str, solved: Solved) {
+    match solved {
+        Solved::Cached => println!("{label}: {answer} (cached)"),
+        Solved::Ran {
+            elapsed,
+            allocs: None,
+        } => println!("{label}: {answer}, in {elapsed:?}"),
+        Solved::Ran {
+            elapsed,
+            allocs: Some(allocs),
+        } => println!("{label}: {answer}, in {elapsed:?} ({allocs})"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn second_run_is_cached() {
+        let path = std::env::temp_dir().join(format!("aoc2023_runner_{}.tsv", std::process::id()));
+        let mut calls = 0;
+        let mut solve = |input: &str| {
+            calls += 1;
+            input.len()
+        };
+
+        let mut runner = Runner::new(1, "abc", &["v1"]).with_cache(Cache::open(&path).unwrap());
+        assert!(matches!(runner.solve(1, &mut solve), (a, Solved::Ran { .. }) if a == "3"));
+        assert_eq!(
+            runner.solve(1, &mut solve),
+            ("3".to_string(), Solved::Cached)
+        );
+
+        // a fresh runner picks the answer up from disk
+        let mut runner = Runner::new(1, "abc", &["v1"]).with_cache(Cache::open(&path).unwrap());
+        assert_eq!(runner.solve(1, &mut solve).1, Solved::Cached);
+
+        // but not if the solver has changed
+        let mut runner = Runner::new(1, "abc", &["v2"]).with_cache(Cache::open(&path).unwrap());
+        assert!(matches!(runner.solve(1, &mut solve).1, Solved::Ran { .. }));
+
+        // or its parameters
+        let runner = Runner::new(1, "abc", &["v2"]).with_cache(Cache::open(&path).unwrap());
+        let mut runner = runner.with_parameters((12, 13, 14));
+        assert!(matches!(runner.solve(1, &mut solve).1, Solved::Ran { .. }));
+        assert_eq!(calls, 3);
+
+        fs::remove_file(path).unwrap();
+    }
+}
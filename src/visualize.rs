@@ -0,0 +1,314 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write as _;
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+/// A single 8-bit colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(96, 96, 96);
+    pub const DARK_GREY: Rgb = Rgb(32, 32, 32);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(133, 153, 0);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(181, 137, 0);
+    pub const ORANGE: Rgb = Rgb(203, 75, 22);
+    pub const CYAN: Rgb = Rgb(42, 161, 152);
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// A grid of coloured cells, addressed by (row, column) like the puzzle inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    background: Rgb,
+    cells: Vec<Rgb>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Canvas {
+            width,
+            height,
+            background,
+            cells: vec![background; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Rgb> {
+        if row < self.height && col < self.width {
+            Some(self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    /// Cells outside the canvas are silently ignored
+    pub fn set(&mut self, row: usize, col: usize, color: Rgb) {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col] = color;
+        }
+    }
+
+    /// Renders as a binary (P6) PPM, drawing every cell as a `scale` x `scale` square
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut out = format!("P6\n{width} {height}\n255\n").into_bytes();
+        out.reserve(width * height * 3);
+
+        for row in self.cells.chunks(self.width.max(1)) {
+            for _ in 0..scale {
+                for Rgb(r, g, b) in row {
+                    for _ in 0..scale {
+                        out.extend_from_slice(&[*r, *g, *b]);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders as an SVG, drawing every cell as a `scale` x `scale` square.
+    /// Only cells differing from the background get their own `<rect>`.
+    pub fn to_svg(&self, scale: usize) -> String {
+        let scale = scale.max(1);
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut s = String::new();
+
+        let _ = writeln!(
+            s,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        );
+        let _ = writeln!(
+            s,
+            r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+            self.background.hex()
+        );
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let color = self.cells[row * self.width + col];
+                if color == self.background {
+                    continue;
+                }
+                let _ = writeln!(
+                    s,
+                    r#"<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="{}"/>"#,
+                    col * scale,
+                    row * scale,
+                    color.hex()
+                );
+            }
+        }
+        s += "</svg>\n";
+        s
+    }
+}
+
+/// Anything which can be drawn as a single frame
+pub trait Render {
+    fn render(&self) -> Canvas;
+}
+
+impl Render for Canvas {
+    fn render(&self) -> Canvas {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Svg,
+}
+
+#[cfg(feature = "std")]
+impl ImageFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Svg => "svg",
+        }
+    }
+
+    fn encode(&self, canvas: &Canvas, scale: usize) -> Vec<u8> {
+        match self {
+            ImageFormat::Ppm => canvas.to_ppm(scale),
+            ImageFormat::Svg => canvas.to_svg(scale).into_bytes(),
+        }
+    }
+}
+
+/// Writes a numbered sequence of frames (`frame_00000.ppm`, `frame_00001.ppm`, ...) into a directory
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FrameRecorder {
+    dir: PathBuf,
+    format: ImageFormat,
+    scale: usize,
+    every: usize,
+    offered: usize,
+    written: usize,
+    last_skipped: bool,
+}
+
+#[cfg(feature = "std")]
+impl FrameRecorder {
+    /// Creates the output directory if it doesn't already exist
+    pub fn new(dir: impl AsRef<Path>, format: ImageFormat) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(FrameRecorder {
+            dir: dir.as_ref().to_path_buf(),
+            format,
+            scale: 4,
+            every: 1,
+            offered: 0,
+            written: 0,
+            last_skipped: false,
+        })
+    }
+
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Only write every `n`th frame offered to `record`.
+    /// Skipped frames are never rendered, so this is cheap to call in a hot loop.
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// Offers a frame to the recorder, returning the path if it was written out
+    pub fn record(&mut self, frame: &impl Render) -> io::Result<Option<PathBuf>> {
+        let index = self.offered;
+        self.offered += 1;
+
+        self.last_skipped = !index.is_multiple_of(self.every);
+        if self.last_skipped {
+            Ok(None)
+        } else {
+            self.write(frame).map(Some)
+        }
+    }
+
+    /// Writes out `last` if the previous frame offered was skipped by `every`, so an end state is never lost.
+    /// Returns the number of frames written so far.
+    pub fn flush(&mut self, last: &impl Render) -> io::Result<usize> {
+        if self.last_skipped {
+            self.write(last)?;
+        }
+        Ok(self.written)
+    }
+
+    /// Writes a frame regardless of `every`
+    pub fn write(&mut self, frame: &impl Render) -> io::Result<PathBuf> {
+        let path = self.dir.join(format!(
+            "frame_{:05}.{}",
+            self.written,
+            self.format.extension()
+        ));
+        let mut file = fs::File::create(&path)?;
+        file.write_all(&self.format.encode(&frame.render(), self.scale))?;
+        self.written += 1;
+        self.last_skipped = false;
+        Ok(path)
+    }
+}
+
+/// Looks for `--frames <dir>` (and optionally `--svg`) on the command line
+#[cfg(feature = "std")]
+pub fn recorder_from_args() -> Option<FrameRecorder> {
+    let args: Vec<String> = std::env::args().collect();
+    let dir = args
+        .iter()
+        .position(|a| a == "--frames")
+        .and_then(|i| args.get(i + 1))?;
+    let format = if args.iter().any(|a| a == "--svg") {
+        ImageFormat::Svg
+    } else {
+        ImageFormat::Ppm
+    };
+
+    match FrameRecorder::new(dir, format) {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            eprintln!("unable to record frames to '{dir}': {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ppm_is_scaled() {
+        let mut canvas = Canvas::new(2, 1, Rgb::BLACK);
+        canvas.set(0, 1, Rgb::WHITE);
+
+        let ppm = canvas.to_ppm(2);
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+
+        let body = &ppm[header.len()..];
+        let row = [0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255];
+        assert_eq!(body, [row, row].concat());
+    }
+
+    #[test]
+    fn svg_skips_background() {
+        let mut canvas = Canvas::new(3, 3, Rgb::BLACK);
+        canvas.set(1, 2, Rgb::RED);
+        // out of bounds is ignored
+        canvas.set(3, 0, Rgb::RED);
+
+        let svg = canvas.to_svg(10);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.contains(r##"<rect x="20" y="10" width="10" height="10" fill="#dc322f"/>"##));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn recorder_flushes_skipped_frame() {
+        let dir = std::env::temp_dir().join(format!("aoc2023_frames_{}", std::process::id()));
+        let mut recorder = FrameRecorder::new(&dir, ImageFormat::Svg).unwrap().every(2);
+
+        let canvas = Canvas::new(1, 1, Rgb::BLACK);
+        let written: Vec<_> = (0..4).map(|_| recorder.record(&canvas).unwrap()).collect();
+        assert!(written[0].is_some());
+        assert!(written[1].is_none());
+        assert!(written[2].is_some());
+        assert!(written[3].is_none());
+
+        assert_eq!(recorder.flush(&canvas).unwrap(), 3);
+        // nothing left to flush the second time around
+        assert_eq!(recorder.flush(&canvas).unwrap(), 3);
+        assert!(dir.join("frame_00002.svg").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}